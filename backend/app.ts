@@ -12,11 +12,15 @@ import {
   type ConfigContext,
   createConfigMiddleware,
 } from "./middleware/config.ts";
+import { createAuthMiddleware } from "./middleware/auth.ts";
 import { handleProjectsRequest } from "./handlers/projects.ts";
 import { handleHistoriesRequest } from "./handlers/histories.ts";
 import { handleConversationRequest } from "./handlers/conversations.ts";
 import { handleChatRequest } from "./handlers/chat.ts";
-import { handleAbortRequest } from "./handlers/abort.ts";
+import {
+  handleAbortRequest,
+  handleActiveRequestsRequest,
+} from "./handlers/abort.ts";
 import {
   handleGetConfig,
   handleSaveConfig,
@@ -30,6 +34,7 @@ export interface AppConfig {
   debugMode: boolean;
   staticPath: string;
   cliPath: string; // Actual CLI script path detected by validateClaudeCli
+  authToken?: string; // Required on API requests when set
 }
 
 export function createApp(
@@ -51,6 +56,12 @@ export function createApp(
     }),
   );
 
+  // Authentication middleware - only the desktop app that started the
+  // backend knows the token, so other local web pages can't use the API
+  if (config.authToken) {
+    app.use("/api/*", createAuthMiddleware(config.authToken));
+  }
+
   // Configuration middleware - makes app settings available to all handlers
   app.use(
     "*",
@@ -76,6 +87,10 @@ export function createApp(
     handleAbortRequest(c, requestAbortControllers),
   );
 
+  app.get("/api/requests", (c) =>
+    handleActiveRequestsRequest(c, requestAbortControllers),
+  );
+
   app.post("/api/chat", (c) => handleChatRequest(c, requestAbortControllers));
 
   // Configuration endpoints
@@ -13,6 +13,8 @@ export interface ParsedArgs {
   port: number;
   host: string;
   claudePath?: string;
+  // Set by the desktop app; API requests must then carry it
+  authToken?: string;
 }
 
 export function parseCliArgs(): ParsedArgs {
@@ -63,5 +65,7 @@ export function parseCliArgs(): ParsedArgs {
     port: options.port,
     host: options.host,
     claudePath: options.claudePath,
+    // Read from the environment only, so it never shows up in process lists
+    authToken: getEnv("CLAUDE_WEBUI_AUTH_TOKEN") || undefined,
   };
 }
@@ -35,6 +35,7 @@ async function main(runtime: DenoRuntime) {
     debugMode: args.debug,
     staticPath,
     cliPath: cliPath,
+    authToken: args.authToken,
   });
 
   // Start server (only show this message when everything is ready)
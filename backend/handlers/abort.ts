@@ -1,4 +1,5 @@
 import { Context } from "hono";
+import type { ActiveRequestsResponse } from "../../shared/types.ts";
 import { logger } from "../utils/logger.ts";
 
 /**
@@ -35,3 +36,21 @@ export function handleAbortRequest(
     return c.json({ error: "Request not found or already completed" }, 404);
   }
 }
+
+/**
+ * Handles GET /api/requests requests
+ * Lists the IDs of chat requests that are still running, so the desktop
+ * shell can abort them before stopping the server
+ * @param c - Hono context object with config variables
+ * @param requestAbortControllers - Map of request IDs to AbortControllers
+ * @returns JSON response with active request IDs
+ */
+export function handleActiveRequestsRequest(
+  c: Context,
+  requestAbortControllers: Map<string, AbortController>,
+) {
+  const response: ActiveRequestsResponse = {
+    requestIds: Array.from(requestAbortControllers.keys()),
+  };
+  return c.json(response);
+}
@@ -0,0 +1,38 @@
+import { describe, it, expect } from "vitest";
+import { Hono } from "hono";
+import { createAuthMiddleware } from "./auth";
+
+describe("Auth Middleware", () => {
+  const app = new Hono();
+  app.use("/api/*", createAuthMiddleware("secret-token"));
+  app.get("/api/projects", (c) => c.json({ projects: [] }));
+  app.get("/index.html", (c) => c.text("ok"));
+
+  it("accepts requests carrying the token", async () => {
+    const response = await app.request("/api/projects", {
+      headers: { Authorization: "Bearer secret-token" },
+    });
+
+    expect(response.status).toBe(200);
+  });
+
+  it("rejects requests without the token", async () => {
+    const response = await app.request("/api/projects");
+
+    expect(response.status).toBe(401);
+  });
+
+  it("rejects requests with a different token", async () => {
+    const response = await app.request("/api/projects", {
+      headers: { Authorization: "Bearer secret-tokem" },
+    });
+
+    expect(response.status).toBe(401);
+  });
+
+  it("leaves routes outside the API alone", async () => {
+    const response = await app.request("/index.html");
+
+    expect(response.status).toBe(200);
+  });
+});
@@ -0,0 +1,38 @@
+import { createMiddleware } from "hono/factory";
+
+/**
+ * Creates authentication middleware that rejects requests without the given
+ * token in an `Authorization: Bearer <token>` header. The desktop app
+ * generates the token on each launch and attaches it to every API request it
+ * forwards, so pages in a local browser cannot reach the API.
+ *
+ * @param token Token expected from clients
+ * @returns Hono middleware function
+ */
+export function createAuthMiddleware(token: string) {
+  const expected = `Bearer ${token}`;
+
+  return createMiddleware(async (c, next) => {
+    // Preflight requests never carry credentials
+    if (c.req.method === "OPTIONS") {
+      await next();
+      return;
+    }
+
+    if (!tokensMatch(c.req.header("Authorization") ?? "", expected)) {
+      return c.json({ error: "Unauthorized" }, 401);
+    }
+
+    await next();
+  });
+}
+
+// Compares in constant time, so response timing doesn't reveal how much of
+// a guessed token was right
+function tokensMatch(actual: string, expected: string): boolean {
+  let difference = actual.length ^ expected.length;
+  for (let i = 0; i < expected.length; i++) {
+    difference |= (actual.charCodeAt(i) || 0) ^ expected.charCodeAt(i);
+  }
+  return difference === 0;
+}
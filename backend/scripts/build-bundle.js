@@ -5,9 +5,38 @@
  *
  * This script bundles the Node.js CLI application using esbuild.
  * Version information is handled via the auto-generated version.ts file.
+ *
+ * With --standalone, every dependency is inlined into a single
+ * dist/standalone/backend.mjs that runs without node_modules. The desktop app
+ * ships this file next to a pinned Node.js runtime (see
+ * scripts/prepare-sidecar.js).
  */
 
 import { build } from "esbuild";
+import process from "node:process";
+
+const standalone = process.argv.includes("--standalone");
+
+const options = standalone
+  ? {
+      outfile: "dist/standalone/backend.mjs",
+      // CommonJS dependencies call require() for Node built-ins, which ESM
+      // output does not provide on its own
+      banner: {
+        js: 'import { createRequire } from "node:module"; const require = createRequire(import.meta.url);',
+      },
+      sourcemap: false,
+    }
+  : {
+      outfile: "dist/cli/node.js",
+      external: [
+        "@anthropic-ai/claude-code",
+        "@hono/node-server",
+        "hono",
+        "commander",
+      ],
+      sourcemap: true,
+    };
 
 // Build with esbuild
 await build({
@@ -16,14 +45,11 @@ await build({
   platform: "node",
   target: "node18",
   format: "esm",
-  outfile: "dist/cli/node.js",
-  external: [
-    "@anthropic-ai/claude-code",
-    "@hono/node-server",
-    "hono",
-    "commander",
-  ],
-  sourcemap: true,
+  ...options,
 });
 
-console.log("✅ Bundle created successfully");
+console.log(
+  standalone
+    ? "✅ Standalone bundle created successfully"
+    : "✅ Bundle created successfully",
+);
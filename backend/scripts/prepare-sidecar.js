@@ -0,0 +1,150 @@
+#!/usr/bin/env node
+
+/**
+ * Prepare the desktop app's backend sidecar
+ *
+ * Downloads the pinned Node.js runtime for the target platform (verified
+ * against the release's SHASUMS256.txt) into src-tauri/binaries/, where Tauri
+ * picks it up as an external binary, and copies the standalone backend bundle
+ * into src-tauri/sidecar/ together with its SHA-256. The app embeds that
+ * checksum at compile time and refuses to start a bundle that doesn't match.
+ *
+ * Usage: node scripts/prepare-sidecar.js [--target <rust-target-triple>]
+ */
+
+import { execFileSync } from "node:child_process";
+import { createHash } from "node:crypto";
+import {
+  chmodSync,
+  copyFileSync,
+  existsSync,
+  mkdirSync,
+  mkdtempSync,
+  readFileSync,
+  rmSync,
+  writeFileSync,
+} from "node:fs";
+import { tmpdir } from "node:os";
+import { dirname, join } from "node:path";
+import { fileURLToPath } from "node:url";
+import process from "node:process";
+
+// Node.js release shipped with the desktop app
+const NODE_VERSION = "22.20.0";
+
+const __filename = fileURLToPath(import.meta.url);
+const __dirname = dirname(__filename);
+
+const bundlePath = join(__dirname, "../dist/standalone/backend.mjs");
+const tauriDir = join(__dirname, "../../src-tauri");
+const binariesDir = join(tauriDir, "binaries");
+const sidecarDir = join(tauriDir, "sidecar");
+
+// Rust target triple -> Node.js distribution name and archive type
+const NODE_DISTRIBUTIONS = {
+  "x86_64-unknown-linux-gnu": ["linux-x64", "tar.xz"],
+  "aarch64-unknown-linux-gnu": ["linux-arm64", "tar.xz"],
+  "x86_64-apple-darwin": ["darwin-x64", "tar.gz"],
+  "aarch64-apple-darwin": ["darwin-arm64", "tar.gz"],
+  "x86_64-pc-windows-msvc": ["win-x64", "zip"],
+  "aarch64-pc-windows-msvc": ["win-arm64", "zip"],
+};
+
+function targetTriple() {
+  const index = process.argv.indexOf("--target");
+  if (index !== -1 && process.argv[index + 1]) {
+    return process.argv[index + 1];
+  }
+  if (process.env.TAURI_ENV_TARGET_TRIPLE) {
+    return process.env.TAURI_ENV_TARGET_TRIPLE;
+  }
+  const rustcInfo = execFileSync("rustc", ["-vV"], { encoding: "utf-8" });
+  const host = rustcInfo.match(/^host: (\S+)$/m);
+  if (!host) {
+    throw new Error("Could not determine the target triple from rustc -vV");
+  }
+  return host[1];
+}
+
+function sha256(data) {
+  return createHash("sha256").update(data).digest("hex");
+}
+
+async function download(url) {
+  const response = await fetch(url);
+  if (!response.ok) {
+    throw new Error(`GET ${url} failed: ${response.status}`);
+  }
+  return Buffer.from(await response.arrayBuffer());
+}
+
+async function prepareRuntime(triple) {
+  const distribution = NODE_DISTRIBUTIONS[triple];
+  if (!distribution) {
+    throw new Error(`No Node.js distribution for target ${triple}`);
+  }
+  const [platform, extension] = distribution;
+  const windows = platform.startsWith("win");
+  const target = join(binariesDir, `node-${triple}${windows ? ".exe" : ""}`);
+
+  const name = `node-v${NODE_VERSION}-${platform}`;
+  const archiveName = `${name}.${extension}`;
+  const releaseUrl = `https://nodejs.org/dist/v${NODE_VERSION}`;
+
+  const checksums = (await download(`${releaseUrl}/SHASUMS256.txt`)).toString();
+  const expected = checksums
+    .split("\n")
+    .map((line) => line.trim().split(/\s+/))
+    .find(([, file]) => file === archiveName)?.[0];
+  if (!expected) {
+    throw new Error(`${archiveName} is not listed in SHASUMS256.txt`);
+  }
+
+  console.log(`📥 Downloading Node.js ${NODE_VERSION} (${platform})...`);
+  const archive = await download(`${releaseUrl}/${archiveName}`);
+  if (sha256(archive) !== expected) {
+    throw new Error(`Checksum mismatch for ${archiveName}`);
+  }
+
+  // bsdtar, which ships with Windows 10+, also extracts zip archives
+  const workDir = mkdtempSync(join(tmpdir(), "claude-webui-node-"));
+  try {
+    const archivePath = join(workDir, archiveName);
+    writeFileSync(archivePath, archive);
+    execFileSync("tar", ["-xf", archivePath, "-C", workDir]);
+
+    mkdirSync(binariesDir, { recursive: true });
+    const executable = windows
+      ? join(workDir, name, "node.exe")
+      : join(workDir, name, "bin", "node");
+    copyFileSync(executable, target);
+    chmodSync(target, 0o755);
+  } finally {
+    rmSync(workDir, { recursive: true, force: true });
+  }
+
+  console.log(`✅ Node.js runtime copied to ${target}`);
+}
+
+function prepareBundle() {
+  if (!existsSync(bundlePath)) {
+    console.error("❌ Standalone bundle not found at:", bundlePath);
+    console.error("   Please run 'npm run build:standalone' first");
+    process.exit(1);
+  }
+
+  mkdirSync(sidecarDir, { recursive: true });
+  const bundle = readFileSync(bundlePath);
+  writeFileSync(join(sidecarDir, "backend.mjs"), bundle);
+  writeFileSync(join(sidecarDir, "backend.sha256"), `${sha256(bundle)}\n`);
+
+  console.log(`✅ Backend bundle copied to ${sidecarDir}`);
+}
+
+try {
+  prepareBundle();
+  await prepareRuntime(targetTriple());
+} catch (error) {
+  console.error("❌ Failed to prepare the sidecar:", error.message);
+  process.exit(1);
+}
@@ -0,0 +1,60 @@
+#!/usr/bin/env node
+
+/**
+ * Stage the backend for the desktop app's bundle
+ *
+ * Copies package.json, package-lock.json and the built dist/ into
+ * src-tauri/staged-backend/ and installs the production dependencies there,
+ * so the bundle ships without the backend's devDependencies (TypeScript,
+ * esbuild, vitest, ...). tauri.conf.json bundles that directory as the
+ * backend resource.
+ *
+ * Usage: node scripts/stage-desktop.js
+ */
+
+import { execFileSync } from "node:child_process";
+import { copyFileSync, cpSync, existsSync, mkdirSync, rmSync } from "node:fs";
+import { dirname, join, relative, sep } from "node:path";
+import { fileURLToPath } from "node:url";
+import process from "node:process";
+
+const __filename = fileURLToPath(import.meta.url);
+const __dirname = dirname(__filename);
+
+const backendDir = join(__dirname, "..");
+const distDir = join(backendDir, "dist");
+const stagingDir = join(__dirname, "../../src-tauri/staged-backend");
+
+if (!existsSync(join(distDir, "cli/node.js"))) {
+  console.error("❌ Backend build not found at:", distDir);
+  console.error("   Please run 'npm run build' first");
+  process.exit(1);
+}
+
+try {
+  rmSync(stagingDir, { recursive: true, force: true });
+  mkdirSync(stagingDir, { recursive: true });
+
+  for (const file of ["package.json", "package-lock.json"]) {
+    copyFileSync(join(backendDir, file), join(stagingDir, file));
+  }
+  // The standalone bundle only serves the sidecar build
+  cpSync(distDir, join(stagingDir, "dist"), {
+    recursive: true,
+    filter: (source) =>
+      !relative(distDir, source).split(sep).includes("standalone"),
+  });
+
+  console.log("📦 Installing production dependencies...");
+  execFileSync("npm", ["ci", "--omit=dev", "--no-audit", "--no-fund"], {
+    cwd: stagingDir,
+    stdio: "inherit",
+    // npm is a batch file on Windows
+    shell: process.platform === "win32",
+  });
+
+  console.log(`✅ Backend staged in ${stagingDir}`);
+} catch (error) {
+  console.error("❌ Failed to stage the backend:", error.message);
+  process.exit(1);
+}
@@ -1,9 +1,15 @@
-import { BrowserRouter as Router, Routes, Route } from "react-router-dom";
+import {
+  BrowserRouter as Router,
+  Routes,
+  Route,
+  useNavigate,
+} from "react-router-dom";
 import { Suspense, lazy } from "react";
 import { ProjectSelector } from "./components/ProjectSelector";
 import { ChatPage } from "./components/ChatPage";
 import { SettingsProvider } from "./contexts/SettingsContext";
 import { isDevelopment } from "./utils/environment";
+import { useDesktopEvent } from "./hooks/useDesktopEvent";
 
 // Lazy load DemoPage only in development
 const DemoPage = isDevelopment()
@@ -14,10 +20,29 @@ const DemoPage = isDevelopment()
     )
   : null;
 
+// Projects and conversations the desktop app was asked to open from the
+// command line, a claude-webui:// link or a notification (see
+// src-tauri/src/launch.rs and notifications.rs)
+function DesktopOpenHandler() {
+  const navigate = useNavigate();
+  useDesktopEvent<{ projectPath: string; sessionId?: string | null }>(
+    "app://open",
+    ({ projectPath, sessionId }) => {
+      const path = projectPath.startsWith("/") ? projectPath : `/${projectPath}`;
+      const search = sessionId
+        ? `?sessionId=${encodeURIComponent(sessionId)}`
+        : "";
+      navigate(`/projects${path}${search}`);
+    },
+  );
+  return null;
+}
+
 function App() {
   return (
     <SettingsProvider>
       <Router>
+        <DesktopOpenHandler />
         <Routes>
           <Route path="/" element={<ProjectSelector />} />
           <Route path="/projects/*" element={<ChatPage />} />
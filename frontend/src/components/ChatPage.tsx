@@ -15,6 +15,7 @@ import { useAbortController } from "../hooks/chat/useAbortController";
 import { useAutoHistoryLoader } from "../hooks/useHistoryLoader";
 import { useSidebarState } from "../hooks/useSidebarState";
 import { useConversationList } from "../hooks/useConversationList";
+import { useDesktopMenu } from "../hooks/useDesktopMenu";
 import { SettingsButton } from "./SettingsButton";
 import { SettingsModal } from "./SettingsModal";
 import { ChatInput } from "./chat/ChatInput";
@@ -23,6 +24,8 @@ import { ConversationSidebar } from "./ConversationSidebar";
 import { getChatUrl, getProjectsUrl } from "../config/api";
 import { KEYBOARD_SHORTCUTS } from "../utils/constants";
 import { normalizeWindowsPath } from "../utils/pathUtils";
+import { isDesktopApp } from "../utils/environment";
+import { streamDesktopChat } from "../utils/desktopChat";
 import type { StreamingContext } from "../hooks/streaming/useMessageProcessor";
 
 export function ChatPage() {
@@ -49,7 +52,7 @@ export function ChatPage() {
   const sessionId = searchParams.get("sessionId");
   const isLoadedConversation = !!sessionId;
 
-  const { processStreamLine } = useClaudeStreaming();
+  const { processStreamLine, processStreamResponse } = useClaudeStreaming();
   const { abortRequest, createAbortHandler } = useAbortController();
 
   // Permission mode state management
@@ -170,23 +173,14 @@ export function ChatPage() {
       startRequest();
 
       try {
-        const response = await fetch(getChatUrl(), {
-          method: "POST",
-          headers: { "Content-Type": "application/json" },
-          body: JSON.stringify({
-            message: content,
-            requestId,
-            ...(currentSessionId ? { sessionId: currentSessionId } : {}),
-            allowedTools: tools || allowedTools,
-            ...(workingDirectory ? { workingDirectory } : {}),
-            permissionMode: overridePermissionMode || permissionMode,
-          } as ChatRequest),
-        });
-
-        if (!response.body) throw new Error("No response body");
-
-        const reader = response.body.getReader();
-        const decoder = new TextDecoder();
+        const chatRequest: ChatRequest = {
+          message: content,
+          requestId,
+          ...(currentSessionId ? { sessionId: currentSessionId } : {}),
+          allowedTools: tools || allowedTools,
+          ...(workingDirectory ? { workingDirectory } : {}),
+          permissionMode: overridePermissionMode || permissionMode,
+        };
 
         // Local state for this streaming session
         let localHasReceivedInit = false;
@@ -214,19 +208,37 @@ export function ChatPage() {
           },
         };
 
-        while (true) {
-          const { done, value } = await reader.read();
-          if (done || shouldAbort) break;
+        if (isDesktopApp()) {
+          // The shell frames the stream and passes typed messages over IPC
+          await streamDesktopChat(chatRequest, (data) => {
+            if (!shouldAbort) processStreamResponse(data, streamingContext);
+          });
+        } else {
+          const response = await fetch(getChatUrl(), {
+            method: "POST",
+            headers: { "Content-Type": "application/json" },
+            body: JSON.stringify(chatRequest),
+          });
+
+          if (!response.body) throw new Error("No response body");
 
-          const chunk = decoder.decode(value);
-          const lines = chunk.split("\n").filter((line) => line.trim());
+          const reader = response.body.getReader();
+          const decoder = new TextDecoder();
+
+          while (true) {
+            const { done, value } = await reader.read();
+            if (done || shouldAbort) break;
+
+            const chunk = decoder.decode(value);
+            const lines = chunk.split("\n").filter((line) => line.trim());
+
+            for (const line of lines) {
+              if (shouldAbort) break;
+              processStreamLine(line, streamingContext);
+            }
 
-          for (const line of lines) {
             if (shouldAbort) break;
-            processStreamLine(line, streamingContext);
           }
-
-          if (shouldAbort) break;
         }
       } catch (error) {
         console.error("Failed to send message:", error);
@@ -260,6 +272,7 @@ export function ChatPage() {
       setCurrentAssistantMessage,
       resetRequestState,
       processStreamLine,
+      processStreamResponse,
       handlePermissionError,
       createAbortHandler,
     ],
@@ -412,6 +425,41 @@ export function ChatPage() {
     }
   }, [navigate, workingDirectory]);
 
+  const handleExport = useCallback(async () => {
+    if (!messages.length) return;
+    const contents = JSON.stringify(
+      {
+        sessionId: currentSessionId,
+        workingDirectory,
+        exportedAt: new Date().toISOString(),
+        messages,
+      },
+      null,
+      2,
+    );
+    const fileName = `conversation-${currentSessionId?.slice(0, 8) ?? "new"}.json`;
+    try {
+      const { invoke } = await import("@tauri-apps/api/core");
+      await invoke("export_conversation", { fileName, contents });
+    } catch (error) {
+      console.error("Failed to export conversation:", error);
+    }
+  }, [messages, currentSessionId, workingDirectory]);
+
+  // Native menu actions in the desktop app
+  useDesktopMenu({
+    "new-chat": handleNewChat,
+    "open-project": (path) => {
+      navigate(`/projects${path.startsWith("/") ? path : `/${path}`}`);
+    },
+    abort: () => {
+      if (isLoading && currentRequestId) {
+        handleAbort();
+      }
+    },
+    export: handleExport,
+  });
+
   // Handle global keyboard shortcuts
   useEffect(() => {
     const handleGlobalKeyDown = (e: KeyboardEvent) => {
@@ -5,6 +5,7 @@ import type { ProjectsResponse, ProjectInfo } from "../types";
 import { getProjectsUrl } from "../config/api";
 import { SettingsButton } from "./SettingsButton";
 import { SettingsModal } from "./SettingsModal";
+import { useDesktopMenu } from "../hooks/useDesktopMenu";
 
 export function ProjectSelector() {
   const [projects, setProjects] = useState<ProjectInfo[]>([]);
@@ -42,6 +43,9 @@ export function ProjectSelector() {
     navigate(`/projects${normalizedPath}`);
   };
 
+  // File > Open Project in the desktop app
+  useDesktopMenu({ "open-project": handleProjectSelect });
+
   const handleSettingsClick = () => {
     setIsSettingsOpen(true);
   };
@@ -1,4 +1,6 @@
-// API configuration - uses relative paths with Vite proxy in development
+// API configuration - uses relative paths with Vite proxy in development.
+// The desktop app injects window.__BACKEND_URL__, a custom protocol that
+// proxies to its backend, and streams chat responses over IPC instead.
 export const API_CONFIG = {
   ENDPOINTS: {
     CHAT: "/api/chat",
@@ -11,28 +13,30 @@ export const API_CONFIG = {
 
 // Helper function to get full API URL
 export const getApiUrl = (endpoint: string) => {
-  return endpoint;
+  return `${window.__BACKEND_URL__ ?? ""}${endpoint}`;
 };
 
 // Helper function to get abort URL
 export const getAbortUrl = (requestId: string) => {
-  return `${API_CONFIG.ENDPOINTS.ABORT}/${requestId}`;
+  return getApiUrl(`${API_CONFIG.ENDPOINTS.ABORT}/${requestId}`);
 };
 
 // Helper function to get chat URL
 export const getChatUrl = () => {
-  return API_CONFIG.ENDPOINTS.CHAT;
+  return getApiUrl(API_CONFIG.ENDPOINTS.CHAT);
 };
 
 // Helper function to get projects URL
 export const getProjectsUrl = () => {
-  return API_CONFIG.ENDPOINTS.PROJECTS;
+  return getApiUrl(API_CONFIG.ENDPOINTS.PROJECTS);
 };
 
 // Helper function to get histories URL
 export const getHistoriesUrl = (projectPath: string) => {
   const encodedPath = encodeURIComponent(projectPath);
-  return `${API_CONFIG.ENDPOINTS.HISTORIES}/${encodedPath}/histories`;
+  return getApiUrl(
+    `${API_CONFIG.ENDPOINTS.HISTORIES}/${encodedPath}/histories`,
+  );
 };
 
 // Helper function to get conversation URL
@@ -40,5 +44,7 @@ export const getConversationUrl = (
   encodedProjectName: string,
   sessionId: string,
 ) => {
-  return `${API_CONFIG.ENDPOINTS.CONVERSATIONS}/${encodedProjectName}/histories/${sessionId}`;
+  return getApiUrl(
+    `${API_CONFIG.ENDPOINTS.CONVERSATIONS}/${encodedProjectName}/histories/${sessionId}`,
+  );
 };
@@ -1,9 +1,15 @@
 import { useCallback } from "react";
 import { getAbortUrl } from "../../config/api";
+import { abortDesktopChat } from "../../utils/desktopChat";
+import { isDesktopApp } from "../../utils/environment";
 
 export function useAbortController() {
   // Helper function to perform abort request
   const performAbortRequest = useCallback(async (requestId: string) => {
+    if (isDesktopApp()) {
+      await abortDesktopChat(requestId);
+      return;
+    }
     await fetch(getAbortUrl(requestId), {
       method: "POST",
       headers: { "Content-Type": "application/json" },
@@ -93,41 +93,48 @@ export function useStreamParser() {
     [processor, adaptContext],
   );
 
+  const processStreamResponse = useCallback(
+    (data: StreamResponse, context: StreamingContext) => {
+      if (data.type === "claude_json" && data.data) {
+        // data.data is already an SDKMessage object, no need to parse
+        const claudeData = data.data as SDKMessage;
+        processClaudeData(claudeData, context);
+      } else if (data.type === "error") {
+        const errorMessage: SystemMessage = {
+          type: "error",
+          subtype: "stream_error",
+          message: data.error || "Unknown error",
+          timestamp: Date.now(),
+        };
+        context.addMessage(errorMessage);
+      } else if (data.type === "aborted") {
+        const abortedMessage: AbortMessage = {
+          type: "system",
+          subtype: "abort",
+          message: "Operation was aborted by user",
+          timestamp: Date.now(),
+        };
+        context.addMessage(abortedMessage);
+        context.setCurrentAssistantMessage(null);
+      }
+    },
+    [processClaudeData],
+  );
+
   const processStreamLine = useCallback(
     (line: string, context: StreamingContext) => {
       try {
         const data: StreamResponse = JSON.parse(line);
-
-        if (data.type === "claude_json" && data.data) {
-          // data.data is already an SDKMessage object, no need to parse
-          const claudeData = data.data as SDKMessage;
-          processClaudeData(claudeData, context);
-        } else if (data.type === "error") {
-          const errorMessage: SystemMessage = {
-            type: "error",
-            subtype: "stream_error",
-            message: data.error || "Unknown error",
-            timestamp: Date.now(),
-          };
-          context.addMessage(errorMessage);
-        } else if (data.type === "aborted") {
-          const abortedMessage: AbortMessage = {
-            type: "system",
-            subtype: "abort",
-            message: "Operation was aborted by user",
-            timestamp: Date.now(),
-          };
-          context.addMessage(abortedMessage);
-          context.setCurrentAssistantMessage(null);
-        }
+        processStreamResponse(data, context);
       } catch (parseError) {
         console.error("Failed to parse stream line:", parseError);
       }
     },
-    [processClaudeData],
+    [processStreamResponse],
   );
 
   return {
     processStreamLine,
+    processStreamResponse,
   };
 }
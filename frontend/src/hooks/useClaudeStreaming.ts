@@ -2,9 +2,10 @@
 import { useStreamParser } from "./streaming/useStreamParser";
 
 export function useClaudeStreaming() {
-  const { processStreamLine } = useStreamParser();
+  const { processStreamLine, processStreamResponse } = useStreamParser();
 
   return {
     processStreamLine,
+    processStreamResponse,
   };
 }
@@ -0,0 +1,48 @@
+import { useEffect, useRef } from "react";
+import { isDesktopApp } from "../utils/environment";
+
+/**
+ * Subscribe to an event the desktop shell emits to this window. Does nothing
+ * outside the desktop app.
+ */
+export function useDesktopEvent<T>(
+  event: string,
+  handler: (payload: T) => void,
+) {
+  // The listener is registered once; the handler may change on every render
+  const handlerRef = useRef(handler);
+  useEffect(() => {
+    handlerRef.current = handler;
+  });
+
+  useEffect(() => {
+    if (!isDesktopApp()) return;
+
+    let disposed = false;
+    let unlisten: (() => void) | undefined;
+
+    const subscribe = async () => {
+      const { getCurrentWebviewWindow } = await import(
+        "@tauri-apps/api/webviewWindow"
+      );
+      const stop = await getCurrentWebviewWindow().listen<T>(
+        event,
+        (received) => handlerRef.current(received.payload),
+      );
+      if (disposed) {
+        stop();
+      } else {
+        unlisten = stop;
+      }
+    };
+
+    subscribe().catch((error) => {
+      console.error(`Failed to subscribe to ${event}:`, error);
+    });
+
+    return () => {
+      disposed = true;
+      unlisten?.();
+    };
+  }, [event]);
+}
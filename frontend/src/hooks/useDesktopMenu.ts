@@ -0,0 +1,26 @@
+import { useDesktopEvent } from "./useDesktopEvent";
+
+/**
+ * Actions of the desktop app's native menu that the frontend carries out.
+ * The shell emits them as `menu://<action>` events to the focused window
+ * (see src-tauri/src/menu.rs); `open-project` carries the chosen directory.
+ */
+export interface DesktopMenuHandlers {
+  "new-chat"?: () => void;
+  "open-project"?: (path: string) => void;
+  abort?: () => void;
+  export?: () => void;
+}
+
+/**
+ * Subscribe to native menu actions for this window. Does nothing outside the
+ * desktop app.
+ */
+export function useDesktopMenu(handlers: DesktopMenuHandlers) {
+  useDesktopEvent("menu://new-chat", () => handlers["new-chat"]?.());
+  useDesktopEvent<string>("menu://open-project", (path) =>
+    handlers["open-project"]?.(path),
+  );
+  useDesktopEvent("menu://abort", () => handlers.abort?.());
+  useDesktopEvent("menu://export", () => handlers.export?.());
+}
@@ -2,6 +2,8 @@
 declare global {
   interface Window {
     showDirectoryPicker?: () => Promise<FileSystemDirectoryHandle>;
+    // Set by the desktop shell before the page loads (see src-tauri/src/splash.rs)
+    __BACKEND_URL__?: string | null;
   }
 
   interface FileSystemDirectoryHandle {
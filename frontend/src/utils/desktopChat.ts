@@ -0,0 +1,56 @@
+import type { ChatRequest, StreamResponse } from "../types";
+
+// The shell holds back messages once 64 are unacknowledged
+// (MAX_IN_FLIGHT in src-tauri/src/chat.rs); acknowledge well before that
+const ACK_BATCH_SIZE = 16;
+
+/**
+ * Send a chat request through the desktop shell, which reads the backend's
+ * stream and hands over each message on an IPC channel
+ * (see src-tauri/src/chat.rs), holding further messages back until earlier
+ * ones are acknowledged. Resolves once every message has been passed to
+ * `onMessage`.
+ */
+export async function streamDesktopChat(
+  request: ChatRequest,
+  onMessage: (message: StreamResponse) => void,
+): Promise<void> {
+  const { Channel, invoke } = await import("@tauri-apps/api/core");
+
+  let received = 0;
+  let expected: number | null = null;
+  let drained: () => void = () => {};
+  const allReceived = new Promise<void>((resolve) => {
+    drained = resolve;
+  });
+
+  const channel = new Channel<StreamResponse>();
+  channel.onmessage = (message) => {
+    try {
+      onMessage(message);
+    } finally {
+      received += 1;
+      if (received % ACK_BATCH_SIZE === 0) {
+        invoke("chat_ack", { requestId: request.requestId, received }).catch(
+          (error) => console.error("Failed to acknowledge messages:", error),
+        );
+      }
+      if (expected !== null && received >= expected) drained();
+    }
+  };
+
+  // The command resolves with how many messages it sent, which may be before
+  // the last of them has been delivered
+  expected = await invoke<number>("chat", { request, onEvent: channel });
+  if (received >= expected) drained();
+  await allReceived;
+}
+
+/**
+ * Abort a chat request sent with streamDesktopChat; its stream then ends with
+ * an "aborted" message
+ */
+export async function abortDesktopChat(requestId: string): Promise<void> {
+  const { invoke } = await import("@tauri-apps/api/core");
+  await invoke("abort_chat", { requestId });
+}
@@ -17,3 +17,11 @@ export function isDevelopment(): boolean {
 export function isProduction(): boolean {
   return import.meta.env.PROD;
 }
+
+/**
+ * Check if the app is running inside the Tauri desktop shell
+ * @returns true in the desktop app, false in a regular browser
+ */
+export function isDesktopApp(): boolean {
+  return typeof window !== "undefined" && "__TAURI_INTERNALS__" in window;
+}
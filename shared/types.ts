@@ -17,6 +17,10 @@ export interface AbortRequest {
   requestId: string;
 }
 
+export interface ActiveRequestsResponse {
+  requestIds: string[];
+}
+
 export interface ProjectInfo {
   path: string;
   encodedName: string;
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ChatRequest = { message: string, sessionId?: string, requestId: string, allowedTools?: Array<string>, workingDirectory?: string, permissionMode?: "default" | "plan" | "acceptEdits", };
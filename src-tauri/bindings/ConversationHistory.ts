@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ConversationHistory = { sessionId: string, messages: unknown[], metadata: { startTime: string, endTime: string, messageCount: number, }, };
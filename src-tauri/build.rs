@@ -1,3 +1,15 @@
 fn main() {
+  // Checksum of the sidecar backend bundle, written by
+  // backend/scripts/prepare-sidecar.js and verified before every launch
+  println!("cargo:rerun-if-changed=sidecar/backend.sha256");
+  if let Ok(checksum) = std::fs::read_to_string("sidecar/backend.sha256") {
+    println!("cargo:rustc-env=BACKEND_SIDECAR_SHA256={}", checksum.trim());
+  }
+
+  // The production copy of the backend bundled as a resource, staged by
+  // backend/scripts/stage-desktop.js before release builds; debug builds run
+  // the checkout's backend and only need the directory to exist
+  let _ = std::fs::create_dir_all("staged-backend");
+
   tauri_build::build()
 }
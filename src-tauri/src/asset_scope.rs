@@ -0,0 +1,162 @@
+use std::path::{Path, PathBuf};
+
+use tauri::{AppHandle, Manager};
+
+// The asset protocol starts out with an empty scope (tauri.conf.json); the
+// webview may load files from Claude's projects and the app's own
+// directories, plus any project the user picks later
+pub fn init(app: &AppHandle) {
+    let path = app.path();
+    let app_dirs = [
+        path.app_config_dir(),
+        path.app_data_dir(),
+        path.app_local_data_dir(),
+        path.app_cache_dir(),
+        path.app_log_dir(),
+    ];
+    let dirs: Vec<PathBuf> = app_dirs
+        .into_iter()
+        .filter_map(Result::ok)
+        .chain(claude_projects())
+        .collect();
+    log::info!("Asset protocol scope: {} directories", dirs.len());
+    for dir in dirs {
+        allow(app, &dir);
+    }
+}
+
+// A project the user chose in a dialog
+pub fn allow_project(app: &AppHandle, project: &Path) {
+    log::info!("Adding {} to the asset protocol scope", project.display());
+    allow(app, project);
+}
+
+fn allow(app: &AppHandle, dir: &Path) {
+    if let Err(e) = app.asset_protocol_scope().allow_directory(dir, true) {
+        log::warn!(
+            "Failed to allow {} in the asset scope: {}",
+            dir.display(),
+            e
+        );
+    }
+}
+
+// Projects Claude has been used in, the keys of `projects` in ~/.claude.json
+// (see backend/handlers/projects.ts)
+fn claude_projects() -> Vec<PathBuf> {
+    dirs::home_dir()
+        .map(|home| projects_in(&home.join(".claude.json")))
+        .unwrap_or_default()
+}
+
+// Existing project directories listed in a Claude config file; a relative
+// key would resolve against our working directory, so only absolute ones count
+fn projects_in(config_path: &Path) -> Vec<PathBuf> {
+    let config = match std::fs::read_to_string(config_path) {
+        Ok(config) => config,
+        Err(e) => {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                log::warn!("Failed to read {}: {}", config_path.display(), e);
+            }
+            return Vec::new();
+        }
+    };
+    let config: serde_json::Value = match serde_json::from_str(&config) {
+        Ok(config) => config,
+        Err(e) => {
+            log::warn!("Failed to parse {}: {}", config_path.display(), e);
+            return Vec::new();
+        }
+    };
+    config
+        .get("projects")
+        .and_then(|projects| projects.as_object())
+        .map(|projects| {
+            projects
+                .keys()
+                .map(PathBuf::from)
+                .filter(|project| project.is_absolute() && project.is_dir())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    // A config file next to a project directory, removed when the test ends
+    struct Home(PathBuf);
+
+    impl Home {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!(
+                "claude-webui-scope-{}-{}",
+                name,
+                std::process::id()
+            ));
+            fs::create_dir_all(dir.join("repo")).unwrap();
+            Self(dir)
+        }
+
+        fn config(&self, contents: &str) -> PathBuf {
+            let path = self.0.join(".claude.json");
+            fs::write(&path, contents).unwrap();
+            path
+        }
+
+        fn repo(&self) -> PathBuf {
+            self.0.join("repo")
+        }
+    }
+
+    impl Drop for Home {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn projects_json(keys: &[String]) -> String {
+        let projects: serde_json::Map<String, serde_json::Value> = keys
+            .iter()
+            .map(|key| (key.clone(), serde_json::json!({})))
+            .collect();
+        serde_json::json!({ "projects": projects }).to_string()
+    }
+
+    #[test]
+    fn lists_existing_absolute_projects() {
+        let home = Home::new("projects");
+        let repo = home.repo().to_string_lossy().into_owned();
+        let gone = home.0.join("gone").to_string_lossy().into_owned();
+        let config = home.config(&projects_json(&[repo, gone]));
+        assert_eq!(projects_in(&config), vec![home.repo()]);
+    }
+
+    #[test]
+    fn ignores_relative_keys() {
+        let home = Home::new("relative");
+        // Exists relative to the working directory of `cargo test`
+        let config = home.config(&projects_json(&["src".to_string(), ".".to_string()]));
+        assert!(Path::new("src").is_dir());
+        assert!(projects_in(&config).is_empty());
+    }
+
+    #[test]
+    fn missing_or_malformed_configs_add_nothing() {
+        let home = Home::new("malformed");
+        assert!(projects_in(&home.0.join("missing.json")).is_empty());
+        for contents in [
+            "",
+            "{",
+            "[]",
+            "{\"projects\": []}",
+            "{\"projects\": null}",
+            "{}",
+        ] {
+            let config = home.config(contents);
+            assert!(projects_in(&config).is_empty(), "{}", contents);
+        }
+    }
+}
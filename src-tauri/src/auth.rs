@@ -0,0 +1,22 @@
+use std::sync::OnceLock;
+
+use reqwest::blocking::RequestBuilder;
+
+// Where the backend reads its token from (see backend/cli/args.ts)
+pub const TOKEN_ENV: &str = "CLAUDE_WEBUI_AUTH_TOKEN";
+
+// Random token, new on each launch, that the backend requires on API requests.
+// Only the shell sends any: the webview's requests go through the proxy
+// protocol and the `chat` command, so pages in a local browser can't get it.
+pub fn token() -> &'static str {
+    static TOKEN: OnceLock<String> = OnceLock::new();
+    TOKEN.get_or_init(|| {
+        let mut bytes = [0u8; 32];
+        getrandom::fill(&mut bytes).expect("no system random number generator");
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    })
+}
+
+pub fn authorize(request: RequestBuilder) -> RequestBuilder {
+    request.bearer_auth(token())
+}
@@ -0,0 +1,183 @@
+use std::fmt;
+use std::net::{Ipv4Addr, TcpListener};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+
+use crate::auth;
+use crate::host::Host;
+#[cfg(not(feature = "sidecar"))]
+use crate::node;
+use crate::node::NodeError;
+use crate::process;
+use crate::settings::Settings;
+
+// Directory containing the backend's package.json and dist/, overriding the
+// bundled and development locations
+#[cfg(not(feature = "sidecar"))]
+pub const BACKEND_DIR_ENV: &str = "CLAUDE_WEBUI_BACKEND_DIR";
+
+// Entry point relative to the backend directory
+#[cfg(not(feature = "sidecar"))]
+const ENTRY_POINT: &str = "dist/cli/node.js";
+
+#[derive(Debug)]
+pub enum SpawnError {
+    #[cfg(not(feature = "sidecar"))]
+    BackendNotFound {
+        tried: Vec<PathBuf>,
+    },
+    Node(NodeError),
+    // The bundled backend isn't the one this build expects
+    #[cfg(feature = "sidecar")]
+    ChecksumMismatch {
+        path: PathBuf,
+        expected: String,
+        actual: String,
+    },
+    #[cfg(feature = "sidecar")]
+    Sidecar(String),
+    Io(std::io::Error),
+}
+
+impl SpawnError {
+    // Missing files don't appear by retrying, so the supervisor gives up at once
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, SpawnError::Io(_))
+    }
+}
+
+impl fmt::Display for SpawnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(not(feature = "sidecar"))]
+            SpawnError::BackendNotFound { tried } => {
+                write!(
+                    f,
+                    "Backend entry point {} not found. Looked in:",
+                    ENTRY_POINT
+                )?;
+                for dir in tried {
+                    write!(f, "\n  {}", dir.display())?;
+                }
+                Ok(())
+            }
+            SpawnError::Node(e) => e.fmt(f),
+            #[cfg(feature = "sidecar")]
+            SpawnError::ChecksumMismatch {
+                path,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Backend bundle {} failed verification (expected SHA-256 {}, got {}). \
+                 The installation is damaged or was modified; please reinstall the app.",
+                path.display(),
+                expected,
+                actual
+            ),
+            #[cfg(feature = "sidecar")]
+            SpawnError::Sidecar(message) => write!(f, "Backend sidecar: {}", message),
+            SpawnError::Io(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for SpawnError {}
+
+impl From<NodeError> for SpawnError {
+    fn from(e: NodeError) -> Self {
+        SpawnError::Node(e)
+    }
+}
+
+impl From<std::io::Error> for SpawnError {
+    fn from(e: std::io::Error) -> Self {
+        SpawnError::Io(e)
+    }
+}
+
+// Find the backend directory: an explicit override, the copy bundled as a
+// resource (see tauri.conf.json), or the repository checkout in debug builds
+#[cfg(not(feature = "sidecar"))]
+pub fn resolve_backend_dir(host: &Host) -> Result<PathBuf, SpawnError> {
+    let mut candidates = Vec::new();
+    if let Some(dir) = std::env::var_os(BACKEND_DIR_ENV) {
+        candidates.push(PathBuf::from(dir));
+    }
+    if let Ok(resource_dir) = host.resource_dir() {
+        candidates.push(resource_dir.join("backend"));
+    }
+    if cfg!(debug_assertions) {
+        candidates.push(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../backend"));
+    }
+
+    match candidates
+        .iter()
+        .find(|dir| dir.join(ENTRY_POINT).is_file())
+    {
+        Some(dir) => Ok(dir.clone()),
+        None => Err(SpawnError::BackendNotFound { tried: candidates }),
+    }
+}
+
+// Ask the OS for a loopback port nobody is listening on
+pub fn pick_free_port() -> Result<u16, std::io::Error> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    Ok(listener.local_addr()?.port())
+}
+
+pub fn is_port_free(port: u16) -> bool {
+    TcpListener::bind((Ipv4Addr::LOCALHOST, port)).is_ok()
+}
+
+// Start the Node.js backend server
+pub fn spawn(host: &Host, port: u16, settings: &Settings) -> Result<Child, SpawnError> {
+    let mut command = command(host)?;
+    command.arg("--port").arg(port.to_string());
+    settings.apply(&mut command);
+    command
+        .env("PORT", port.to_string())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    // Headless mode serves the UI to a browser, which has no way to get the
+    // token, so only the desktop app's backend requires one
+    match host {
+        Host::App(_) => command.env(auth::TOKEN_ENV, auth::token()),
+        Host::Headless { .. } => command.env_remove(auth::TOKEN_ENV),
+    };
+    process::configure(&mut command);
+    let child = command.spawn()?;
+
+    log::info!("Backend started with PID: {:?}", child.id());
+    Ok(child)
+}
+
+// The backend from a checkout or bundled resources on a discovered Node.js
+#[cfg(not(feature = "sidecar"))]
+fn command(host: &Host) -> Result<Command, SpawnError> {
+    let backend_dir = resolve_backend_dir(host)?;
+    let node = node::discover()?;
+
+    log::info!("Starting backend from: {:?}", backend_dir);
+
+    let mut command = Command::new(&node.path);
+    if let Some(bin_dir) = node.bin_dir() {
+        prepend_path(&mut command, bin_dir);
+    }
+    command.arg(ENTRY_POINT).current_dir(&backend_dir);
+    Ok(command)
+}
+
+#[cfg(feature = "sidecar")]
+fn command(host: &Host) -> Result<Command, SpawnError> {
+    crate::sidecar::command(host)
+}
+
+// Put `dir` first on the backend's PATH
+pub fn prepend_path(command: &mut Command, dir: &Path) {
+    let path = std::env::var_os("PATH").unwrap_or_default();
+    let paths = std::iter::once(dir.to_path_buf()).chain(std::env::split_paths(&path));
+    if let Ok(path) = std::env::join_paths(paths) {
+        command.env("PATH", path);
+    }
+}
@@ -0,0 +1,597 @@
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use tauri::ipc::Channel;
+use tauri::{Emitter, WebviewWindow};
+
+use crate::auth;
+use crate::notifications::{self, ChatOutcome, ChatOutcomeKind};
+use crate::shutdown;
+use crate::types::{ChatRequest, StreamResponse, StreamResponseType};
+
+const READ_BUFFER_SIZE: usize = 8 * 1024;
+
+// Longest line buffered while waiting for its end; the backend's messages are
+// far shorter, so a longer one means the stream is broken
+const MAX_LINE_LENGTH: usize = 4 * 1024 * 1024;
+
+// How many messages may be on their way to the page before the backend's
+// stream is read any further; the page acknowledges them in batches (see
+// frontend/src/utils/desktopChat.ts)
+const MAX_IN_FLIGHT: u64 = 64;
+
+// How often a stream waiting for acknowledgements checks if it was abandoned
+const ABANDONED_CHECK_INTERVAL: Duration = Duration::from_millis(250);
+
+// Emitted whenever a chat starts or ends; the count is in `ChatStreams`
+pub const ACTIVE_EVENT: &str = "chat://active";
+
+// Splits the NDJSON body into lines as it arrives. Lines end at b'\n', which
+// never occurs inside a multi-byte UTF-8 sequence, so a character split across
+// reads is only decoded once the rest of its line is in.
+#[derive(Default)]
+struct LineDecoder {
+    pending: Vec<u8>,
+}
+
+impl LineDecoder {
+    fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.pending.extend_from_slice(chunk);
+        let Some(end) = self.pending.iter().rposition(|&byte| byte == b'\n') else {
+            return Vec::new();
+        };
+        let complete: Vec<u8> = self.pending.drain(..=end).collect();
+        complete
+            .split(|&byte| byte == b'\n')
+            .filter_map(decode)
+            .collect()
+    }
+
+    // Whether the unfinished line has grown past MAX_LINE_LENGTH
+    fn overflowed(&self) -> bool {
+        self.pending.len() > MAX_LINE_LENGTH
+    }
+
+    // Whatever followed the last newline, once the stream has ended
+    fn finish(&mut self) -> Option<String> {
+        decode(&std::mem::take(&mut self.pending))
+    }
+}
+
+fn decode(line: &[u8]) -> Option<String> {
+    let line = match std::str::from_utf8(line) {
+        Ok(line) => line.trim().to_string(),
+        Err(e) => {
+            log::warn!("Chat stream line is not valid UTF-8: {}", e);
+            String::from_utf8_lossy(line).trim().to_string()
+        }
+    };
+    (!line.is_empty()).then_some(line)
+}
+
+// Follows a chat's messages to tell how it ended, the way the page's message
+// processor reads them (frontend/src/utils/UnifiedMessageProcessor.ts)
+#[derive(Default)]
+struct OutcomeWatcher {
+    session_id: Option<String>,
+    // Tool names by tool_use ID, for naming the tool a permission error is about
+    tools: HashMap<String, String>,
+    permission_required: bool,
+    denied_tool: Option<String>,
+    error: Option<String>,
+    aborted: bool,
+    done: bool,
+}
+
+impl OutcomeWatcher {
+    fn new(request: &ChatRequest) -> Self {
+        Self {
+            session_id: request.session_id.clone(),
+            ..Self::default()
+        }
+    }
+
+    fn observe(&mut self, message: &StreamResponse) {
+        match message.kind {
+            StreamResponseType::ClaudeJson => {
+                if let Some(data) = &message.data {
+                    self.observe_claude(data);
+                }
+            }
+            StreamResponseType::Error => {
+                self.error = Some(message.error.clone().unwrap_or_default());
+            }
+            StreamResponseType::Aborted => self.aborted = true,
+            StreamResponseType::Done => self.done = true,
+        }
+    }
+
+    fn observe_claude(&mut self, data: &serde_json::Value) {
+        if let Some(session_id) = data.get("session_id").and_then(|id| id.as_str()) {
+            self.session_id = Some(session_id.to_string());
+        }
+        let content = data
+            .pointer("/message/content")
+            .and_then(|content| content.as_array())
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        for item in content {
+            let field = |name: &str| item.get(name).and_then(|value| value.as_str());
+            match field("type") {
+                Some("tool_use") => {
+                    if let (Some(id), Some(name)) = (field("id"), field("name")) {
+                        self.tools.insert(id.to_string(), name.to_string());
+                    }
+                }
+                // A failed tool result other than a tool_use_error is Claude
+                // being denied permission; the page aborts the request and
+                // asks the user
+                Some("tool_result")
+                    if item.get("is_error").and_then(|e| e.as_bool()) == Some(true) =>
+                {
+                    let content = match item.get("content") {
+                        Some(serde_json::Value::String(content)) => content.clone(),
+                        Some(content) => content.to_string(),
+                        None => String::new(),
+                    };
+                    if !content.contains("tool_use_error") {
+                        self.permission_required = true;
+                        self.denied_tool = field("tool_use_id")
+                            .and_then(|id| self.tools.get(id))
+                            .cloned();
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // None when there is nothing to tell: the user aborted the request, or
+    // the stream was cut off
+    fn finish(self, request: &ChatRequest, result: &Result<u64, String>) -> Option<ChatOutcome> {
+        let (kind, detail) = if self.permission_required {
+            (ChatOutcomeKind::PermissionRequired, self.denied_tool)
+        } else if let Some(error) = self.error {
+            (
+                ChatOutcomeKind::Error,
+                Some(error).filter(|e| !e.is_empty()),
+            )
+        } else if let Err(error) = result {
+            (ChatOutcomeKind::Error, Some(error.clone()))
+        } else if self.done && !self.aborted {
+            (ChatOutcomeKind::Completed, None)
+        } else {
+            return None;
+        };
+        Some(ChatOutcome {
+            kind,
+            project_path: request.working_directory.clone(),
+            session_id: self.session_id,
+            detail,
+        })
+    }
+}
+
+// How many of a chat's messages the page has handled
+#[derive(Default)]
+struct Acks {
+    received: Mutex<u64>,
+    changed: Condvar,
+}
+
+impl Acks {
+    fn acknowledge(&self, received: u64) {
+        let mut acknowledged = self.received.lock().unwrap();
+        if received > *acknowledged {
+            *acknowledged = received;
+            self.changed.notify_all();
+        }
+    }
+
+    // Block until fewer than MAX_IN_FLIGHT of the `sent` messages are
+    // unacknowledged; false if the chat was abandoned in the meantime
+    fn wait_for_room(&self, sent: u64, abandoned: &AtomicBool) -> bool {
+        let mut received = self.received.lock().unwrap();
+        while sent.saturating_sub(*received) >= MAX_IN_FLIGHT {
+            if abandoned.load(Ordering::SeqCst) {
+                return false;
+            }
+            received = self
+                .changed
+                .wait_timeout(received, ABANDONED_CHECK_INTERVAL)
+                .unwrap()
+                .0;
+        }
+        true
+    }
+}
+
+struct ActiveChat {
+    webview: String,
+    abandoned: Arc<AtomicBool>,
+    acks: Arc<Acks>,
+}
+
+// Chat requests streamed to the webviews over IPC channels (see the `chat`
+// command), by request ID
+#[derive(Clone, Default)]
+pub struct ChatStreams {
+    active: Arc<Mutex<HashMap<String, ActiveChat>>>,
+}
+
+impl ChatStreams {
+    // Forward the backend's responses to `request` until the stream ends,
+    // then notify the user of how it ended if they're looking elsewhere;
+    // returns how many were sent. Blocks, so it runs off the async runtime.
+    pub fn run(
+        &self,
+        base_url: &str,
+        window: &WebviewWindow,
+        request: ChatRequest,
+        channel: &Channel<StreamResponse>,
+    ) -> Result<u64, String> {
+        let request_id = request.request_id.clone();
+        let abandoned = Arc::new(AtomicBool::new(false));
+        let acks = Arc::new(Acks::default());
+        self.active.lock().unwrap().insert(
+            request_id.clone(),
+            ActiveChat {
+                webview: window.label().to_string(),
+                abandoned: abandoned.clone(),
+                acks: acks.clone(),
+            },
+        );
+        let _ = window.emit(ACTIVE_EVENT, ());
+        log::info!("Chat request {} started in {}", request_id, window.label());
+
+        let mut watcher = OutcomeWatcher::new(&request);
+        let result = forward(base_url, &request, channel, &abandoned, &acks, &mut watcher);
+        self.active.lock().unwrap().remove(&request_id);
+        let _ = window.emit(ACTIVE_EVENT, ());
+        match &result {
+            Ok(sent) => log::info!("Chat request {} ended after {} messages", request_id, sent),
+            Err(e) => log::warn!("Chat request {} failed: {}", request_id, e),
+        }
+
+        // Nobody is left to tell about a chat abandoned by its page
+        if !abandoned.load(Ordering::SeqCst) {
+            if let Some(outcome) = watcher.finish(&request, &result) {
+                notifications::chat_outcome(window, outcome);
+            }
+        }
+        result
+    }
+
+    // The page has handled the first `received` messages of `request_id`
+    pub fn acknowledge(&self, request_id: &str, received: u64) {
+        if let Some(chat) = self.active.lock().unwrap().get(request_id) {
+            chat.acks.acknowledge(received);
+        }
+    }
+
+    // Chats being streamed to any webview; with the desktop app's token on
+    // the API, these are all the backend runs
+    pub fn active_count(&self) -> usize {
+        self.active.lock().unwrap().len()
+    }
+
+    // `webview` reloaded or closed, so nothing receives its chats any more;
+    // stop forwarding them and have the backend abort them
+    pub fn abandon(&self, base_url: Option<String>, webview: &str) {
+        let request_ids: Vec<String> = self
+            .active
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, chat)| chat.webview == webview)
+            .map(|(request_id, chat)| {
+                chat.abandoned.store(true, Ordering::SeqCst);
+                request_id.clone()
+            })
+            .collect();
+        let Some(base_url) = base_url.filter(|_| !request_ids.is_empty()) else {
+            return;
+        };
+        // Called from webview events on the main thread
+        thread::spawn(move || {
+            for request_id in request_ids {
+                log::info!("Aborting abandoned chat request {}...", request_id);
+                if let Err(e) = shutdown::abort_request(&base_url, &request_id) {
+                    log::warn!("Failed to abort request {}: {}", request_id, e);
+                }
+            }
+        });
+    }
+}
+
+// `Channel::send` only queues a message for the webview, so sending stops
+// MAX_IN_FLIGHT messages ahead of the page's acknowledgements; a page that
+// falls behind leaves the rest unread in the backend's stream instead of
+// piling up in the webview
+fn forward(
+    base_url: &str,
+    request: &ChatRequest,
+    channel: &Channel<StreamResponse>,
+    abandoned: &AtomicBool,
+    acks: &Acks,
+    watcher: &mut OutcomeWatcher,
+) -> Result<u64, String> {
+    // No timeout: the stream lasts as long as Claude keeps working
+    let client = reqwest::blocking::Client::builder()
+        .timeout(None)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let mut response = auth::authorize(client.post(format!("{}/api/chat", base_url)))
+        .json(request)
+        .send()
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| e.to_string())?;
+
+    let mut decoder = LineDecoder::default();
+    let mut buffer = [0; READ_BUFFER_SIZE];
+    let mut sent = 0;
+    loop {
+        let read = response.read(&mut buffer).map_err(|e| e.to_string())?;
+        let lines = match read {
+            0 => decoder.finish().into_iter().collect(),
+            _ => decoder.push(&buffer[..read]),
+        };
+        for line in lines {
+            if abandoned.load(Ordering::SeqCst) {
+                return Ok(sent);
+            }
+            let message = match serde_json::from_str::<StreamResponse>(&line) {
+                Ok(message) => message,
+                Err(e) => {
+                    log::warn!("Skipping malformed chat stream line: {}", e);
+                    continue;
+                }
+            };
+            if !acks.wait_for_room(sent, abandoned) {
+                return Ok(sent);
+            }
+            watcher.observe(&message);
+            channel.send(message).map_err(|e| e.to_string())?;
+            sent += 1;
+        }
+        if read == 0 {
+            return Ok(sent);
+        }
+        if decoder.overflowed() {
+            log::warn!(
+                "Chat request {} sent a line over {} bytes",
+                request.request_id,
+                MAX_LINE_LENGTH
+            );
+            if let Err(e) = shutdown::abort_request(base_url, &request.request_id) {
+                log::warn!("Failed to abort request {}: {}", request.request_id, e);
+            }
+            let message = StreamResponse {
+                kind: StreamResponseType::Error,
+                data: None,
+                error: Some("The chat stream sent a message that is too large.".to_string()),
+            };
+            watcher.observe(&message);
+            channel.send(message).map_err(|e| e.to_string())?;
+            return Ok(sent + 1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn decodes_character_split_across_reads() {
+        let line = "{\"text\":\"héllo ✓\"}\n".as_bytes();
+        let split = line.iter().position(|&byte| byte == 0xC3).unwrap() + 1;
+        let mut decoder = LineDecoder::default();
+        assert!(decoder.push(&line[..split]).is_empty());
+        assert_eq!(decoder.push(&line[split..]), vec!["{\"text\":\"héllo ✓\"}"]);
+        assert_eq!(decoder.finish(), None);
+    }
+
+    #[test]
+    fn joins_line_split_across_reads() {
+        let mut decoder = LineDecoder::default();
+        assert_eq!(decoder.push(b"{\"a\":1}\n{\"b\""), vec!["{\"a\":1}"]);
+        assert!(decoder.push(b":2").is_empty());
+        assert_eq!(
+            decoder.push(b"}\n{\"c\":3}\n"),
+            vec!["{\"b\":2}", "{\"c\":3}"]
+        );
+    }
+
+    #[test]
+    fn finish_returns_unterminated_last_line() {
+        let mut decoder = LineDecoder::default();
+        assert_eq!(decoder.push(b"{\"a\":1}\n{\"b\":2}"), vec!["{\"a\":1}"]);
+        assert_eq!(decoder.finish().as_deref(), Some("{\"b\":2}"));
+        assert_eq!(decoder.finish(), None);
+    }
+
+    #[test]
+    fn overflows_on_a_line_without_end() {
+        let mut decoder = LineDecoder::default();
+        let chunk = vec![b'x'; READ_BUFFER_SIZE];
+        while !decoder.overflowed() {
+            assert!(decoder.push(&chunk).is_empty());
+        }
+        assert_eq!(decoder.pending.len(), MAX_LINE_LENGTH + READ_BUFFER_SIZE);
+
+        // Long lines are fine as long as they end
+        let mut decoder = LineDecoder::default();
+        let mut line = vec![b'x'; MAX_LINE_LENGTH];
+        line.push(b'\n');
+        assert_eq!(decoder.push(&line).len(), 1);
+        assert!(!decoder.overflowed());
+    }
+
+    #[test]
+    fn skips_blank_lines() {
+        let mut decoder = LineDecoder::default();
+        assert_eq!(
+            decoder.push(b"\n\r\n  \n{\"a\":1}\r\n\n"),
+            vec!["{\"a\":1}"]
+        );
+        assert!(decoder.push(b"\n").is_empty());
+        assert_eq!(decoder.finish(), None);
+    }
+
+    #[test]
+    fn waits_for_acknowledgements() {
+        let acks = Arc::new(Acks::default());
+        let abandoned = AtomicBool::new(false);
+        assert!(acks.wait_for_room(MAX_IN_FLIGHT - 1, &abandoned));
+
+        let page = {
+            let acks = acks.clone();
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(50));
+                acks.acknowledge(16);
+            })
+        };
+        assert!(acks.wait_for_room(MAX_IN_FLIGHT, &abandoned));
+        page.join().unwrap();
+        assert_eq!(*acks.received.lock().unwrap(), 16);
+    }
+
+    #[test]
+    fn abandoned_chats_stop_waiting() {
+        let acks = Acks::default();
+        let abandoned = AtomicBool::new(true);
+        assert!(!acks.wait_for_room(MAX_IN_FLIGHT, &abandoned));
+    }
+
+    fn request() -> ChatRequest {
+        ChatRequest {
+            message: "Fix the tests".to_string(),
+            session_id: Some("previous-session".to_string()),
+            request_id: "request-1".to_string(),
+            allowed_tools: None,
+            working_directory: Some("/home/user/repo".to_string()),
+            permission_mode: None,
+        }
+    }
+
+    fn message(kind: StreamResponseType, data: Option<serde_json::Value>) -> StreamResponse {
+        StreamResponse {
+            kind,
+            data,
+            error: None,
+        }
+    }
+
+    fn claude(data: serde_json::Value) -> StreamResponse {
+        message(StreamResponseType::ClaudeJson, Some(data))
+    }
+
+    fn outcome(messages: &[StreamResponse]) -> Option<ChatOutcome> {
+        let request = request();
+        let mut watcher = OutcomeWatcher::new(&request);
+        for message in messages {
+            watcher.observe(message);
+        }
+        watcher.finish(&request, &Ok(messages.len() as u64))
+    }
+
+    fn tool_use(id: &str, name: &str) -> StreamResponse {
+        claude(json!({
+            "type": "assistant",
+            "session_id": "new-session",
+            "message": { "content": [{ "type": "tool_use", "id": id, "name": name, "input": {} }] },
+        }))
+    }
+
+    fn tool_result(id: &str, content: &str) -> StreamResponse {
+        claude(json!({
+            "type": "user",
+            "session_id": "new-session",
+            "message": {
+                "content": [{ "type": "tool_result", "tool_use_id": id, "content": content, "is_error": true }],
+            },
+        }))
+    }
+
+    #[test]
+    fn completed_chat() {
+        let outcome = outcome(&[
+            claude(json!({ "type": "system", "subtype": "init", "session_id": "new-session" })),
+            message(StreamResponseType::Done, None),
+        ]);
+        assert_eq!(
+            outcome,
+            Some(ChatOutcome {
+                kind: ChatOutcomeKind::Completed,
+                project_path: Some("/home/user/repo".to_string()),
+                session_id: Some("new-session".to_string()),
+                detail: None,
+            })
+        );
+    }
+
+    #[test]
+    fn backend_error() {
+        let outcome = outcome(&[StreamResponse {
+            kind: StreamResponseType::Error,
+            data: None,
+            error: Some("Claude Code process exited with code 1".to_string()),
+        }])
+        .unwrap();
+        assert_eq!(outcome.kind, ChatOutcomeKind::Error);
+        assert_eq!(outcome.session_id.as_deref(), Some("previous-session"));
+        assert_eq!(
+            outcome.detail.as_deref(),
+            Some("Claude Code process exited with code 1")
+        );
+    }
+
+    #[test]
+    fn failed_request() {
+        let request = request();
+        let outcome = OutcomeWatcher::new(&request)
+            .finish(&request, &Err("connection refused".to_string()))
+            .unwrap();
+        assert_eq!(outcome.kind, ChatOutcomeKind::Error);
+        assert_eq!(outcome.detail.as_deref(), Some("connection refused"));
+    }
+
+    #[test]
+    fn permission_denied_then_aborted_by_the_page() {
+        let outcome = outcome(&[
+            tool_use("tool-1", "Bash"),
+            tool_result("tool-1", "Claude requested permissions to use Bash"),
+            message(StreamResponseType::Aborted, None),
+        ])
+        .unwrap();
+        assert_eq!(outcome.kind, ChatOutcomeKind::PermissionRequired);
+        assert_eq!(outcome.detail.as_deref(), Some("Bash"));
+        assert_eq!(outcome.session_id.as_deref(), Some("new-session"));
+    }
+
+    #[test]
+    fn tool_use_errors_are_not_permission_requests() {
+        let outcome = outcome(&[
+            tool_use("tool-1", "Read"),
+            tool_result(
+                "tool-1",
+                "<tool_use_error>File does not exist.</tool_use_error>",
+            ),
+            message(StreamResponseType::Done, None),
+        ])
+        .unwrap();
+        assert_eq!(outcome.kind, ChatOutcomeKind::Completed);
+    }
+
+    #[test]
+    fn aborted_or_cut_off_chats_are_not_reported() {
+        assert_eq!(outcome(&[message(StreamResponseType::Aborted, None)]), None);
+        assert_eq!(outcome(&[tool_use("tool-1", "Read")]), None);
+    }
+}
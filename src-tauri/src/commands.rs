@@ -0,0 +1,193 @@
+use std::thread;
+
+use tauri::ipc::Channel;
+use tauri::{AppHandle, State, WebviewWindow};
+use tauri_plugin_dialog::DialogExt;
+
+use crate::chat::ChatStreams;
+use crate::logs::{BackendLogs, LogLine};
+use crate::menu;
+use crate::node::{self, NodeError, NodeRuntime};
+use crate::settings::{Settings, SettingsStore};
+use crate::shutdown;
+use crate::supervisor::{BackendStatus, Supervisor};
+use crate::types::{ChatRequest, StreamResponse};
+use crate::windows;
+
+// Base URL of the backend, e.g. "http://127.0.0.1:49152"
+#[tauri::command]
+pub fn get_backend_url(supervisor: State<'_, Supervisor>) -> Result<String, String> {
+    supervisor
+        .base_url()
+        .ok_or_else(|| "Backend port has not been allocated yet".to_string())
+}
+
+// Current backend state; changes also arrive as `backend://status` events
+#[tauri::command]
+pub fn backend_status(supervisor: State<'_, Supervisor>) -> BackendStatus {
+    supervisor.status()
+}
+
+// Start the backend if it isn't running or being restarted already
+#[tauri::command]
+pub fn start_backend(supervisor: State<'_, Supervisor>) -> BackendStatus {
+    supervisor.start();
+    supervisor.status()
+}
+
+// Stopping waits for in-flight requests to be aborted and the process tree
+// to exit, so it runs off the async runtime
+#[tauri::command]
+pub async fn stop_backend(supervisor: State<'_, Supervisor>) -> Result<BackendStatus, String> {
+    let supervisor = supervisor.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        supervisor.shutdown();
+        supervisor.status()
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn restart_backend(supervisor: State<'_, Supervisor>) -> Result<BackendStatus, String> {
+    let supervisor = supervisor.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        supervisor.restart();
+        supervisor.status()
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+// Most recent backend output lines, oldest first; new lines follow as
+// `backend://log` events
+#[tauri::command]
+pub fn get_backend_logs(logs: State<'_, BackendLogs>, limit: Option<usize>) -> Vec<LogLine> {
+    logs.tail(limit)
+}
+
+// Node.js runtime the backend would be started with, or why there is none
+#[tauri::command]
+pub async fn check_node_runtime() -> Result<NodeRuntime, NodeError> {
+    node::discover()
+}
+
+// Desktop settings as stored in the app config directory
+#[tauri::command]
+pub fn get_settings(store: State<'_, SettingsStore>) -> Settings {
+    store.get()
+}
+
+// Save new settings, restarting the backend in the background when they
+// change how it is started; returns the settings as saved
+#[tauri::command]
+pub fn update_settings(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    supervisor: State<'_, Supervisor>,
+    settings: Settings,
+) -> Result<Settings, String> {
+    let previous = store.get();
+    let (saved, restart) = store.update(settings)?;
+    if saved.shortcuts != previous.shortcuts {
+        menu::install(&app).map_err(|e| e.to_string())?;
+    }
+    if restart {
+        let supervisor = supervisor.inner().clone();
+        thread::spawn(move || supervisor.restart());
+    }
+    Ok(saved)
+}
+
+// Open another window on the shared backend, showing `project_path` if given;
+// returns the new window's label. Async because creating a window from a
+// synchronous command deadlocks on Windows.
+#[tauri::command]
+pub async fn new_window(app: AppHandle, project_path: Option<String>) -> Result<String, String> {
+    windows::open(&app, project_path.as_deref(), None).map(|window| window.label().to_string())
+}
+
+// Save an exported conversation where the user picks; returns the path, or
+// nothing if the save dialog was cancelled
+#[tauri::command]
+pub async fn export_conversation(
+    app: AppHandle,
+    file_name: String,
+    contents: String,
+) -> Result<Option<String>, String> {
+    let Some(path) = app
+        .dialog()
+        .file()
+        .set_title("Export Conversation")
+        .set_file_name(file_name)
+        .blocking_save_file()
+    else {
+        return Ok(None);
+    };
+    let path = path.into_path().map_err(|e| e.to_string())?;
+    std::fs::write(&path, contents)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(Some(path.to_string_lossy().into_owned()))
+}
+
+// Silence, or restore, notifications for a project; returns the settings as saved
+#[tauri::command]
+pub fn set_project_muted(
+    store: State<'_, SettingsStore>,
+    project_path: String,
+    muted: bool,
+) -> Result<Settings, String> {
+    let mut settings = store.get();
+    if muted {
+        settings.muted_projects.insert(project_path);
+    } else {
+        settings.muted_projects.remove(&project_path);
+    }
+    store.update(settings).map(|(saved, _)| saved)
+}
+
+// Send a chat request and stream its responses to `on_event`, in place of the
+// page reading /api/chat itself; resolves with how many were sent once the
+// stream has ended
+#[tauri::command]
+pub async fn chat(
+    window: WebviewWindow,
+    supervisor: State<'_, Supervisor>,
+    streams: State<'_, ChatStreams>,
+    request: ChatRequest,
+    on_event: Channel<StreamResponse>,
+) -> Result<u64, String> {
+    let base_url = supervisor
+        .base_url()
+        .ok_or_else(|| "The backend server is not running".to_string())?;
+    let streams = streams.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        streams.run(&base_url, &window, request, &on_event)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// The page has handled the first `received` messages of a chat request
+// started with `chat`, which holds back further messages until it does
+#[tauri::command]
+pub fn chat_ack(streams: State<'_, ChatStreams>, request_id: String, received: u64) {
+    streams.acknowledge(&request_id, received);
+}
+
+// Abort a chat request started with `chat`; its stream then ends with an
+// `aborted` message
+#[tauri::command]
+pub async fn abort_chat(
+    supervisor: State<'_, Supervisor>,
+    request_id: String,
+) -> Result<(), String> {
+    let base_url = supervisor
+        .base_url()
+        .ok_or_else(|| "The backend server is not running".to_string())?;
+    tauri::async_runtime::spawn_blocking(move || {
+        shutdown::abort_request(&base_url, &request_id).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,312 @@
+use serde::Serialize;
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+use crate::backend::SpawnError;
+use crate::node::NodeError;
+
+// Logged by validateClaudeCli in backend/cli/validation.ts before it exits
+const CLAUDE_NOT_FOUND_MARKER: &str = "Claude CLI not found in PATH";
+const CLAUDE_INVALID_MARKER: &str = "Failed to validate Claude CLI";
+const CLAUDE_ERROR_PREFIX: &str = "Error: ";
+
+// Node's error code when listen() finds the port taken
+const PORT_IN_USE_MARKER: &str = "EADDRINUSE";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FailureKind {
+    NodeMissing,
+    BackendMissing,
+    PortInUse,
+    ClaudeCli,
+    EarlyExit,
+    NotReady,
+    Other,
+}
+
+// Why the backend could not be started and what the user can do about it
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnosis {
+    pub kind: FailureKind,
+    pub summary: String,
+    pub remediation: String,
+    // Everything known about the failure, for bug reports
+    pub details: String,
+}
+
+impl Diagnosis {
+    // Failures that retrying with the same installation and settings won't fix
+    pub fn is_permanent(&self) -> bool {
+        !matches!(
+            self.kind,
+            FailureKind::PortInUse
+                | FailureKind::EarlyExit
+                | FailureKind::NotReady
+                | FailureKind::Other
+        )
+    }
+}
+
+// The backend process could not be spawned at all
+pub fn spawn_failure(error: &SpawnError) -> Diagnosis {
+    let details = error.to_string();
+    match error {
+        SpawnError::Node(NodeError::NotFound { .. }) => Diagnosis {
+            kind: FailureKind::NodeMissing,
+            summary: "Node.js is not installed.".to_string(),
+            remediation: format!(
+                "Install Node.js {} or newer from https://nodejs.org, or set {} to the node \
+                 executable, then restart the app.",
+                crate::node::MIN_NODE_VERSION,
+                crate::node::NODE_PATH_ENV
+            ),
+            details,
+        },
+        SpawnError::Node(NodeError::TooOld {
+            version, required, ..
+        }) => Diagnosis {
+            kind: FailureKind::NodeMissing,
+            summary: format!("Node.js {} is too old.", version),
+            remediation: format!(
+                "Upgrade Node.js to {} or newer, or set {} to a newer node executable, then \
+                 restart the app.",
+                required,
+                crate::node::NODE_PATH_ENV
+            ),
+            details,
+        },
+        #[cfg(not(feature = "sidecar"))]
+        SpawnError::BackendNotFound { .. } => Diagnosis {
+            kind: FailureKind::BackendMissing,
+            summary: "The backend server files are missing.".to_string(),
+            remediation: format!(
+                "Reinstall the app. When running from source, build the backend with `npm run \
+                 build:backend` or set {} to a built backend directory.",
+                crate::backend::BACKEND_DIR_ENV
+            ),
+            details,
+        },
+        #[cfg(feature = "sidecar")]
+        SpawnError::ChecksumMismatch { .. } | SpawnError::Sidecar(_) => Diagnosis {
+            kind: FailureKind::BackendMissing,
+            summary: "The bundled backend server is damaged or missing.".to_string(),
+            remediation: "Reinstall the app.".to_string(),
+            details,
+        },
+        SpawnError::Io(_) => Diagnosis {
+            kind: FailureKind::Other,
+            summary: "The backend server could not be started.".to_string(),
+            remediation: "Copy the details to see the backend's output, then restart the backend."
+                .to_string(),
+            details,
+        },
+    }
+}
+
+// The backend exited, or never accepted connections, before it was ready.
+// `output` holds its last stderr lines.
+pub fn startup_failure(exit: &str, output: Option<&str>, timed_out: bool) -> Diagnosis {
+    let lines: Vec<&str> = output
+        .map(|output| output.lines().collect())
+        .unwrap_or_default();
+    let mut details = format!("Backend exit: {}", exit);
+    if let Some(output) = output {
+        details.push_str("\n\n");
+        details.push_str(output);
+    }
+
+    if lines
+        .iter()
+        .any(|line| line.contains(CLAUDE_NOT_FOUND_MARKER))
+    {
+        return Diagnosis {
+            kind: FailureKind::ClaudeCli,
+            summary: "The Claude CLI was not found.".to_string(),
+            remediation: "Install Claude Code (see https://claude.ai/code), or set its location \
+                          in Settings under \"Claude path\", then restart the backend."
+                .to_string(),
+            details,
+        };
+    }
+    if let Some(index) = lines
+        .iter()
+        .position(|line| line.contains(CLAUDE_INVALID_MARKER))
+    {
+        let reason = lines[index + 1..].iter().find_map(|line| {
+            line.split_once(CLAUDE_ERROR_PREFIX)
+                .map(|(_, reason)| reason.trim().to_string())
+        });
+        return Diagnosis {
+            kind: FailureKind::ClaudeCli,
+            summary: match reason {
+                Some(reason) => format!("The Claude CLI failed validation: {}", reason),
+                None => "The Claude CLI failed validation.".to_string(),
+            },
+            remediation: "Check that `claude --version` works in a terminal, or set the Claude \
+                          executable in Settings under \"Claude path\", then restart the backend."
+                .to_string(),
+            details,
+        };
+    }
+    if lines.iter().any(|line| line.contains(PORT_IN_USE_MARKER)) {
+        return Diagnosis {
+            kind: FailureKind::PortInUse,
+            summary: "The backend's port is already in use.".to_string(),
+            remediation: "Another program took the port the backend was about to use. Restart \
+                          the backend and it will pick a free port."
+                .to_string(),
+            details,
+        };
+    }
+    if timed_out {
+        return Diagnosis {
+            kind: FailureKind::NotReady,
+            summary: "The backend server did not become ready in time.".to_string(),
+            remediation: "Check the log files for what the backend was doing, then restart it."
+                .to_string(),
+            details,
+        };
+    }
+    Diagnosis {
+        kind: FailureKind::EarlyExit,
+        summary: format!("The backend server exited during startup ({}).", exit),
+        remediation: "Copy the details to see the backend's output, then restart the backend."
+            .to_string(),
+        details,
+    }
+}
+
+// Native error dialog whose extra button copies the details for a bug report
+pub fn show_dialog(app: &AppHandle, diagnosis: &Diagnosis) {
+    let message = format!("{}\n\n{}", diagnosis.summary, diagnosis.remediation);
+    let details = format!(
+        "{}\n\n{}\n\n{}",
+        diagnosis.summary, diagnosis.remediation, diagnosis.details
+    );
+    let handle = app.clone();
+    app.dialog()
+        .message(message)
+        .title("Claude Code could not start its backend")
+        .kind(MessageDialogKind::Error)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Copy Details".to_string(),
+            "Close".to_string(),
+        ))
+        .show(move |copy| {
+            if copy {
+                if let Err(e) = handle.clipboard().write_text(details) {
+                    log::error!("Failed to copy failure details: {}", e);
+                }
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Backend stderr as logged by backend/cli/validation.ts and Node
+    const CLAUDE_NOT_FOUND: &str = "\
+❌ Claude CLI not found in PATH
+   Please install claude-code globally:
+   Visit: https://claude.ai/code for installation instructions";
+
+    const CLAUDE_INVALID: &str = "\
+🔍 Validating Claude CLI...
+❌ Failed to validate Claude CLI
+   Error: Command failed: claude --version";
+
+    const CLAUDE_INVALID_NO_REASON: &str = "❌ Failed to validate Claude CLI";
+
+    const PORT_IN_USE: &str = "\
+node:events:496
+      throw er; // Unhandled 'error' event
+      ^
+
+Error: listen EADDRINUSE: address already in use 127.0.0.1:41823
+    at Server.setupListenHandle [as _listen2] (node:net:1908:16)
+  code: 'EADDRINUSE',";
+
+    const CRASH: &str = "\
+file:///app/backend/dist/cli/node.js:12
+TypeError: Cannot read properties of undefined (reading 'port')";
+
+    #[test]
+    fn classifies_backend_output() {
+        let cases: &[(Option<&str>, bool, FailureKind, &str)] = &[
+            (
+                Some(CLAUDE_NOT_FOUND),
+                false,
+                FailureKind::ClaudeCli,
+                "The Claude CLI was not found.",
+            ),
+            (
+                Some(CLAUDE_INVALID),
+                false,
+                FailureKind::ClaudeCli,
+                "The Claude CLI failed validation: Command failed: claude --version",
+            ),
+            (
+                Some(CLAUDE_INVALID_NO_REASON),
+                false,
+                FailureKind::ClaudeCli,
+                "The Claude CLI failed validation.",
+            ),
+            (
+                Some(PORT_IN_USE),
+                false,
+                FailureKind::PortInUse,
+                "The backend's port is already in use.",
+            ),
+            (
+                Some(CRASH),
+                false,
+                FailureKind::EarlyExit,
+                "The backend server exited during startup (exit status: 1).",
+            ),
+            (
+                None,
+                false,
+                FailureKind::EarlyExit,
+                "The backend server exited during startup (exit status: 1).",
+            ),
+            (
+                Some("Listening on 127.0.0.1:41823"),
+                true,
+                FailureKind::NotReady,
+                "The backend server did not become ready in time.",
+            ),
+        ];
+
+        for (output, timed_out, kind, summary) in cases {
+            let diagnosis = startup_failure("exit status: 1", *output, *timed_out);
+            assert_eq!(diagnosis.kind, *kind, "{:?}", output);
+            assert_eq!(diagnosis.summary, *summary, "{:?}", output);
+        }
+    }
+
+    #[test]
+    fn claude_errors_take_precedence_over_timeouts() {
+        let diagnosis = startup_failure("exit status: 1", Some(CLAUDE_NOT_FOUND), true);
+        assert_eq!(diagnosis.kind, FailureKind::ClaudeCli);
+    }
+
+    #[test]
+    fn details_include_exit_and_output() {
+        let diagnosis = startup_failure("exit status: 1", Some(CRASH), false);
+        assert!(diagnosis
+            .details
+            .starts_with("Backend exit: exit status: 1\n\n"));
+        assert!(diagnosis.details.ends_with(CRASH));
+    }
+
+    #[test]
+    fn port_conflicts_are_retried() {
+        let diagnosis = startup_failure("exit status: 1", Some(PORT_IN_USE), false);
+        assert!(!diagnosis.is_permanent());
+        assert!(diagnosis.remediation.contains("Restart the backend"));
+    }
+}
@@ -0,0 +1,237 @@
+use std::sync::{mpsc, Mutex};
+use std::time::Duration;
+
+use log::{LevelFilter, Log, Metadata, Record};
+use tauri::Context;
+
+use crate::host::Host;
+use crate::logs::{BackendLogs, LogFile};
+use crate::settings::SettingsStore;
+use crate::shutdown;
+use crate::supervisor::{BackendState, Supervisor};
+
+const HEADLESS_FLAG: &str = "--headless";
+
+const USAGE: &str = "Usage: app --headless [--port N] [--host H]";
+
+// How often the foreground loop looks at the backend's state
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+struct Options {
+    port: Option<u16>,
+    host: Option<String>,
+}
+
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        port: None,
+        host: None,
+    };
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let (name, inline) = match arg.split_once('=') {
+            Some((name, value)) => (name, Some(value.to_string())),
+            None => (arg.as_str(), None),
+        };
+        let mut value = || {
+            inline
+                .clone()
+                .or_else(|| args.next().cloned())
+                .ok_or_else(|| format!("{} needs a value", name))
+        };
+        match name {
+            HEADLESS_FLAG => {}
+            "--port" => {
+                let port = value()?;
+                options.port = Some(
+                    port.parse()
+                        .map_err(|_| format!("Invalid port: {}", port))?,
+                );
+            }
+            "--host" => options.host = Some(value()?),
+            _ => return Err(format!("Unknown argument: {}", arg)),
+        }
+    }
+    Ok(options)
+}
+
+// Whether the app was started as `app --headless ...`
+pub fn requested() -> bool {
+    std::env::args().skip(1).any(|arg| arg == HEADLESS_FLAG)
+}
+
+// Supervise the backend without a webview, e.g. on a server whose UI is
+// used from a browser. Returns the process exit code.
+pub fn run<R: tauri::Runtime>(context: &Context<R>) -> i32 {
+    attach_console();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let options = match parse_options(&args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n{}", e, USAGE);
+            return 2;
+        }
+    };
+
+    let host = Host::Headless {
+        identifier: context.config().identifier.clone(),
+        package_info: context.package_info().clone(),
+    };
+    let file = host
+        .log_dir()
+        .and_then(|dir| LogFile::open(dir).map_err(|e| e.to_string()));
+    let file = match file {
+        Ok(file) => Some(Mutex::new(file)),
+        Err(e) => {
+            eprintln!("Not writing log files: {}", e);
+            None
+        }
+    };
+    let _ = log::set_boxed_logger(Box::new(HeadlessLogger { file })).map(|()| {
+        log::set_max_level(LevelFilter::Info);
+    });
+
+    let settings = SettingsStore::load(&host);
+    if let Some(bind) = options.host {
+        settings.override_host(bind);
+    }
+    let logs = BackendLogs::new(host.clone());
+    let mut supervisor = Supervisor::new(host, logs, settings);
+    if let Some(port) = options.port {
+        supervisor = supervisor.with_port(port);
+    }
+
+    let (stop, stopped) = mpsc::channel();
+    shutdown::on_termination_signal(move || {
+        let _ = stop.send(());
+    });
+    shutdown::stop_backend_on_panic(supervisor.clone());
+    supervisor.start();
+
+    let mut announced = None;
+    let code = loop {
+        if stopped.recv_timeout(POLL_INTERVAL).is_ok() {
+            break 0;
+        }
+        let status = supervisor.status();
+        match status.state {
+            BackendState::Ready => {
+                let url = supervisor.base_url();
+                if url.is_some() && url != announced {
+                    // The one line on stdout, for scripts waiting on the backend
+                    println!("{}", url.as_deref().unwrap_or_default());
+                    announced = url;
+                }
+            }
+            BackendState::Failed => {
+                match &status.diagnosis {
+                    Some(diagnosis) => {
+                        eprintln!("{}\n{}", diagnosis.summary, diagnosis.remediation)
+                    }
+                    None => eprintln!("The backend server could not be kept running."),
+                }
+                break 1;
+            }
+            _ => {}
+        }
+    };
+
+    supervisor.shutdown();
+    code
+}
+
+// Release builds use the Windows GUI subsystem (see main.rs) and start without
+// a console, so output would go nowhere unless redirected; use the console of
+// the shell headless mode was started from, if there is one
+#[cfg(windows)]
+fn attach_console() {
+    use windows_sys::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_console() {}
+
+// There is no Tauri app to host the log plugin, so shell and backend output
+// goes to stderr, keeping stdout for the URL, and to the same rotating files
+// in the log directory as in app mode
+struct HeadlessLogger {
+    file: Option<Mutex<LogFile>>,
+}
+
+impl Log for HeadlessLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= LevelFilter::Info
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!(
+                "[{}][{}] {}",
+                record.level(),
+                record.target(),
+                record.args()
+            );
+            if let Some(file) = &self.file {
+                file.lock().unwrap().write(record);
+            }
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        parse_options(&args)
+    }
+
+    #[test]
+    fn parses_separate_and_inline_values() {
+        let options = parse(&["--headless", "--port", "8080", "--host", "0.0.0.0"]).unwrap();
+        assert_eq!(options.port, Some(8080));
+        assert_eq!(options.host.as_deref(), Some("0.0.0.0"));
+
+        let options = parse(&["--port=8080", "--headless", "--host=::"]).unwrap();
+        assert_eq!(options.port, Some(8080));
+        assert_eq!(options.host.as_deref(), Some("::"));
+    }
+
+    #[test]
+    fn defaults_without_options() {
+        let options = parse(&["--headless"]).unwrap();
+        assert_eq!(options.port, None);
+        assert_eq!(options.host, None);
+    }
+
+    #[test]
+    fn rejects_invalid_arguments() {
+        assert_eq!(
+            parse(&["--headless", "--port"]).err().unwrap(),
+            "--port needs a value"
+        );
+        assert_eq!(
+            parse(&["--port", "http"]).err().unwrap(),
+            "Invalid port: http"
+        );
+        assert_eq!(
+            parse(&["--port=70000"]).err().unwrap(),
+            "Invalid port: 70000"
+        );
+        assert_eq!(
+            parse(&["--verbose"]).err().unwrap(),
+            "Unknown argument: --verbose"
+        );
+        assert_eq!(
+            parse(&["~/code/repo"]).err().unwrap(),
+            "Unknown argument: ~/code/repo"
+        );
+    }
+}
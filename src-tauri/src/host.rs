@@ -0,0 +1,90 @@
+use std::path::PathBuf;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Env, Manager, PackageInfo};
+
+use crate::paths;
+
+// What backend supervision needs from its surroundings: the running Tauri
+// app, or in headless mode (see headless.rs) just the bundle's identity, with
+// directories resolved the way Tauri would and events going nowhere
+#[derive(Clone)]
+pub enum Host {
+    App(AppHandle),
+    Headless {
+        identifier: String,
+        package_info: PackageInfo,
+    },
+}
+
+impl Host {
+    pub fn emit<S: Serialize + Clone>(&self, event: &str, payload: S) {
+        if let Host::App(app) = self {
+            let _ = app.emit(event, payload);
+        }
+    }
+
+    #[cfg(feature = "sidecar")]
+    pub fn app(&self) -> Option<&AppHandle> {
+        match self {
+            Host::App(app) => Some(app),
+            Host::Headless { .. } => None,
+        }
+    }
+
+    // See paths::runtime_dir
+    pub fn runtime_dir(&self) -> Result<PathBuf, String> {
+        match self {
+            Host::App(app) => paths::runtime_dir(app).map_err(|e| e.to_string()),
+            Host::Headless { identifier, .. } => {
+                let dir = dirs::runtime_dir()
+                    .or_else(dirs::cache_dir)
+                    .ok_or("no runtime or cache directory")?
+                    .join(identifier);
+                std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+                Ok(dir)
+            }
+        }
+    }
+
+    pub fn config_dir(&self) -> Result<PathBuf, String> {
+        match self {
+            Host::App(app) => app.path().app_config_dir().map_err(|e| e.to_string()),
+            Host::Headless { identifier, .. } => dirs::config_dir()
+                .map(|dir| dir.join(identifier))
+                .ok_or_else(|| "no config directory".to_string()),
+        }
+    }
+
+    // Where the log plugin writes in app mode (Tauri's app_log_dir)
+    pub fn log_dir(&self) -> Result<PathBuf, String> {
+        match self {
+            Host::App(app) => app.path().app_log_dir().map_err(|e| e.to_string()),
+            Host::Headless { identifier, .. } => headless_log_dir(identifier),
+        }
+    }
+
+    pub fn resource_dir(&self) -> Result<PathBuf, String> {
+        match self {
+            Host::App(app) => app.path().resource_dir().map_err(|e| e.to_string()),
+            Host::Headless { package_info, .. } => {
+                tauri::utils::platform::resource_dir(package_info, &Env::default())
+                    .map_err(|e| e.to_string())
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn headless_log_dir(identifier: &str) -> Result<PathBuf, String> {
+    dirs::home_dir()
+        .map(|dir| dir.join("Library/Logs").join(identifier))
+        .ok_or_else(|| "no home directory".to_string())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn headless_log_dir(identifier: &str) -> Result<PathBuf, String> {
+    dirs::data_local_dir()
+        .map(|dir| dir.join(identifier).join("logs"))
+        .ok_or_else(|| "no local data directory".to_string())
+}
@@ -0,0 +1,343 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Url};
+
+use crate::splash::SPLASH_WINDOW;
+use crate::windows;
+
+// Custom URL scheme, registered through the deep-link plugin's config in
+// tauri.conf.json
+pub const URL_SCHEME: &str = "claude-webui";
+
+// Payload: `OpenRequest`
+pub const OPEN_EVENT: &str = "app://open";
+
+// A project, and optionally a conversation in it, to show. Comes from the
+// command line (`app ~/code/repo --session <id>`) or a
+// `claude-webui://open?project=...&session=...` link.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenRequest {
+    pub project_path: String,
+    pub session_id: Option<String>,
+}
+
+impl OpenRequest {
+    // `args` without the program name; relative paths are resolved against `cwd`
+    pub fn from_args(args: &[String], cwd: Option<&Path>) -> Option<Self> {
+        let mut project = None;
+        let mut session_id = None;
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            if let Some(value) = arg.strip_prefix("--session=") {
+                session_id = Some(value.to_string());
+            } else if arg == "--session" {
+                session_id = args.next().cloned();
+            } else if arg.starts_with(&format!("{}:", URL_SCHEME)) {
+                return Url::parse(arg).ok().and_then(|url| Self::from_url(&url));
+            } else if !arg.starts_with('-') && project.is_none() {
+                project = Some(arg.clone());
+            }
+        }
+
+        let project = PathBuf::from(project?);
+        let project = match cwd {
+            Some(cwd) if project.is_relative() => cwd.join(project),
+            _ => project,
+        };
+        Some(Self {
+            project_path: existing_dir(&project)?,
+            session_id: session_id.filter(|id| !id.is_empty()),
+        })
+    }
+
+    pub fn from_url(url: &Url) -> Option<Self> {
+        if url.scheme() != URL_SCHEME || url.host_str() != Some("open") {
+            log::warn!("Ignoring unsupported link {}", url);
+            return None;
+        }
+        let mut project = None;
+        let mut session_id = None;
+        for (key, value) in url.query_pairs() {
+            match key.as_ref() {
+                "project" => project = Some(PathBuf::from(value.as_ref())),
+                "session" if !value.is_empty() => session_id = Some(value.into_owned()),
+                _ => {}
+            }
+        }
+        let Some(project) = project.filter(|project| !project.as_os_str().is_empty()) else {
+            log::warn!("Ignoring link without a project: {}", url);
+            return None;
+        };
+        // A link has no working directory to resolve a relative path against
+        if project.is_relative() {
+            log::warn!("Ignoring link with a relative project path: {}", url);
+            return None;
+        }
+        Some(Self {
+            project_path: existing_dir(&project)?,
+            session_id,
+        })
+    }
+}
+
+// `project`, canonicalized, if it is a directory
+fn existing_dir(project: &Path) -> Option<String> {
+    if !project.is_dir() {
+        log::warn!("Ignoring {}: not a directory", project.display());
+        return None;
+    }
+    let project = project
+        .canonicalize()
+        .unwrap_or_else(|_| project.to_path_buf());
+    Some(plain(project.to_string_lossy().into_owned()))
+}
+
+// canonicalize() returns verbatim paths (`\\?\C:\...`) on Windows, which the
+// backend and the project list would show and compare as different paths
+#[cfg(windows)]
+fn plain(path: String) -> String {
+    strip_verbatim(path)
+}
+
+#[cfg(not(windows))]
+fn plain(path: String) -> String {
+    path
+}
+
+#[cfg(any(windows, test))]
+fn strip_verbatim(path: String) -> String {
+    if let Some(share) = path.strip_prefix(r"\\?\UNC\") {
+        return format!(r"\\{}", share);
+    }
+    match path.strip_prefix(r"\\?\") {
+        // Only drive paths; other verbatim paths have no plain form
+        Some(rest) if rest.as_bytes().get(1) == Some(&b':') => rest.to_string(),
+        _ => path,
+    }
+}
+
+// Where the first window goes before any window is open
+#[derive(Default)]
+pub struct PendingOpen(Mutex<Option<OpenRequest>>);
+
+impl PendingOpen {
+    pub fn take(&self) -> Option<OpenRequest> {
+        self.0.lock().unwrap().take()
+    }
+}
+
+// Show the requested project: in the window the user last used if there is
+// one, in the first window once the backend is ready otherwise
+pub fn open(app: &AppHandle, request: OpenRequest) {
+    log::info!("Opening {:?}", request);
+    let windows = app.webview_windows();
+    let app_windows: Vec<_> = windows
+        .values()
+        .filter(|window| window.label() != SPLASH_WINDOW)
+        .collect();
+
+    if app_windows.is_empty() {
+        if windows.contains_key(SPLASH_WINDOW) {
+            *app.state::<PendingOpen>().0.lock().unwrap() = Some(request);
+            return;
+        }
+        // Running in the background
+        if let Err(e) = windows::open(
+            app,
+            Some(&request.project_path),
+            request.session_id.as_deref(),
+        ) {
+            log::error!("Failed to open {}: {}", request.project_path, e);
+        }
+        return;
+    }
+
+    let window = app_windows
+        .iter()
+        .find(|window| window.is_focused().unwrap_or(false))
+        .or_else(|| app_windows.first())
+        .copied();
+    if let Some(window) = window {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+        let _ = window.emit_to(window.label(), OPEN_EVENT, request);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn as_string(path: &Path) -> String {
+        path.to_string_lossy().into_owned()
+    }
+
+    // A directory to open, removed again when the test ends
+    struct Project(PathBuf);
+
+    impl Project {
+        fn new(name: &str) -> Self {
+            // The temp dir itself may be reached through a symlink, e.g.
+            // /var -> /private/var on macOS
+            let temp_dir = existing_dir(&std::env::temp_dir()).unwrap();
+            let dir = Path::new(&temp_dir).join(format!(
+                "claude-webui-launch-{}-{}",
+                name,
+                std::process::id()
+            ));
+            fs::create_dir_all(dir.join("repo")).unwrap();
+            Self(dir)
+        }
+
+        fn repo(&self) -> PathBuf {
+            self.0.join("repo")
+        }
+    }
+
+    impl Drop for Project {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn url(host: &str, project: &Path, session: Option<&str>) -> Url {
+        let mut url = Url::parse(&format!("{}://{}", URL_SCHEME, host)).unwrap();
+        url.query_pairs_mut()
+            .append_pair("project", &project.to_string_lossy());
+        if let Some(session) = session {
+            url.query_pairs_mut().append_pair("session", session);
+        }
+        url
+    }
+
+    #[test]
+    fn session_flag_forms() {
+        let project = Project::new("session");
+        let repo = project.repo().to_string_lossy().into_owned();
+        let expected = Some(OpenRequest {
+            project_path: as_string(&project.repo()),
+            session_id: Some("abc".to_string()),
+        });
+
+        assert_eq!(
+            OpenRequest::from_args(&args(&[&repo, "--session=abc"]), None),
+            expected
+        );
+        assert_eq!(
+            OpenRequest::from_args(&args(&["--session", "abc", &repo]), None),
+            expected
+        );
+        assert_eq!(
+            OpenRequest::from_args(&args(&[&repo, "--session="]), None)
+                .unwrap()
+                .session_id,
+            None
+        );
+    }
+
+    #[test]
+    fn relative_path_resolves_against_cwd() {
+        let project = Project::new("relative");
+        let request = OpenRequest::from_args(&args(&["repo"]), Some(&project.0)).unwrap();
+        assert_eq!(request.project_path, as_string(&project.repo()));
+        assert_eq!(request.session_id, None);
+    }
+
+    #[test]
+    fn rejects_missing_directories() {
+        let project = Project::new("missing");
+        assert_eq!(
+            OpenRequest::from_args(&args(&["gone"]), Some(&project.0)),
+            None
+        );
+        assert_eq!(
+            OpenRequest::from_args(&args(&["--session", "abc"]), None),
+            None
+        );
+    }
+
+    #[test]
+    fn url_passed_as_argument() {
+        let project = Project::new("url-arg");
+        let url = url("open", &project.repo(), Some("abc")).to_string();
+        assert_eq!(
+            OpenRequest::from_args(&args(&[&url]), None),
+            Some(OpenRequest {
+                project_path: as_string(&project.repo()),
+                session_id: Some("abc".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn url_project_must_be_an_existing_absolute_directory() {
+        let project = Project::new("url");
+        assert_eq!(
+            OpenRequest::from_url(&url("open", &project.repo(), None)),
+            Some(OpenRequest {
+                project_path: as_string(&project.repo()),
+                session_id: None,
+            })
+        );
+        assert_eq!(
+            OpenRequest::from_url(&url("open", Path::new("repo"), None)),
+            None
+        );
+        assert_eq!(
+            OpenRequest::from_url(&url("open", &project.0.join("gone"), None)),
+            None
+        );
+
+        let without_project = Url::parse(&format!("{}://open?session=abc", URL_SCHEME)).unwrap();
+        assert_eq!(OpenRequest::from_url(&without_project), None);
+    }
+
+    #[test]
+    fn rejects_unsupported_links() {
+        let project = Project::new("host");
+        assert_eq!(
+            OpenRequest::from_url(&url("settings", &project.repo(), None)),
+            None
+        );
+
+        let mut other_scheme = Url::parse("https://open").unwrap();
+        other_scheme
+            .query_pairs_mut()
+            .append_pair("project", &project.repo().to_string_lossy());
+        assert_eq!(OpenRequest::from_url(&other_scheme), None);
+    }
+
+    #[test]
+    fn resolved_paths_are_plain() {
+        let project = Project::new("plain");
+        let path = existing_dir(&project.repo().join("..").join("repo")).unwrap();
+        assert_eq!(path, as_string(&project.repo()));
+        assert!(!path.starts_with(r"\\?\"));
+    }
+
+    #[test]
+    fn strips_verbatim_prefixes() {
+        assert_eq!(
+            strip_verbatim(r"\\?\C:\Users\me\repo".to_string()),
+            r"C:\Users\me\repo"
+        );
+        assert_eq!(
+            strip_verbatim(r"\\?\UNC\server\share\repo".to_string()),
+            r"\\server\share\repo"
+        );
+        assert_eq!(
+            strip_verbatim(r"\\?\Volume{1234}\repo".to_string()),
+            r"\\?\Volume{1234}\repo"
+        );
+        assert_eq!(strip_verbatim("/home/me/repo".to_string()), "/home/me/repo");
+    }
+}
@@ -1,68 +1,212 @@
-use std::process::{Child, Command};
-use std::sync::Mutex;
-use tauri::Manager;
-
-// Backend process state
-pub struct BackendProcess(pub Mutex<Option<Child>>);
-
-// Start the Node.js backend server
-fn start_backend() -> Result<Child, std::io::Error> {
-    let current_dir = std::env::current_dir()?;
-    let backend_dir = current_dir.join("backend");
-    
-    #[cfg(target_os = "windows")]
-    let node_command = "node.exe";
-    
-    #[cfg(not(target_os = "windows"))]
-    let node_command = "node";
-
-    println!("Starting backend from: {:?}", backend_dir);
-    
-    let child = Command::new(node_command)
-        .arg("dist/cli/node.js")
-        .current_dir(&backend_dir)
-        .spawn()?;
-
-    println!("Backend started with PID: {:?}", child.id());
-    Ok(child)
-}
+mod asset_scope;
+mod auth;
+mod backend;
+mod chat;
+mod commands;
+mod diagnostics;
+mod headless;
+mod host;
+mod launch;
+mod logs;
+mod menu;
+mod node;
+mod notifications;
+mod output;
+mod paths;
+mod process;
+mod proxy;
+mod settings;
+mod shutdown;
+#[cfg(feature = "sidecar")]
+mod sidecar;
+mod single_instance;
+mod splash;
+mod supervisor;
+mod tray;
+pub mod types;
+mod window_state;
+mod windows;
+
+use tauri::webview::PageLoadEvent;
+use tauri::{AppHandle, Manager, RunEvent, WindowEvent};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+use chat::ChatStreams;
+use host::Host;
+use launch::{OpenRequest, PendingOpen};
+use logs::BackendLogs;
+use settings::SettingsStore;
+use supervisor::Supervisor;
+use window_state::WindowStateStore;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tauri::Builder::default()
+    let context = tauri::generate_context!();
+    if headless::requested() {
+        std::process::exit(headless::run(&context));
+    }
+
+    let builder = tauri::Builder::default()
+        .plugin(logs::plugin())
+        // Before anything else starts, so a second launch only hands over
+        // its arguments and exits
+        .plugin(single_instance::plugin())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_deep_link::init())
+        .manage(PendingOpen::default())
+        .manage(ChatStreams::default())
+        .register_asynchronous_uri_scheme_protocol(proxy::SCHEME, |ctx, request, responder| {
+            proxy::handle(ctx.app_handle(), request, responder)
+        });
+    #[cfg(feature = "sidecar")]
+    let builder = builder.plugin(tauri_plugin_shell::init());
+
+    let app = builder
+        .invoke_handler(tauri::generate_handler![
+            commands::get_backend_url,
+            commands::backend_status,
+            commands::start_backend,
+            commands::stop_backend,
+            commands::restart_backend,
+            commands::get_backend_logs,
+            commands::check_node_runtime,
+            commands::get_settings,
+            commands::update_settings,
+            commands::new_window,
+            commands::export_conversation,
+            commands::set_project_muted,
+            commands::chat,
+            commands::chat_ack,
+            commands::abort_chat,
+        ])
         .setup(|app| {
-            // Start backend server on application startup
-            let backend_process = match start_backend() {
-                Ok(child) => {
-                    println!("✓ Backend server started successfully");
-                    Some(child)
-                }
-                Err(e) => {
-                    eprintln!("✗ Failed to start backend: {}", e);
-                    eprintln!("  The application may not function correctly.");
-                    None
+            // Backend output is kept in memory for get_backend_logs
+            let logs = BackendLogs::new(Host::App(app.handle().clone()));
+            app.manage(logs.clone());
+
+            // Settings decide how the backend is started
+            let settings = SettingsStore::load(&Host::App(app.handle().clone()));
+            app.manage(settings.clone());
+
+            // Windows reopen where the last session left them
+            app.manage(WindowStateStore::load(app.handle()));
+
+            // Start backend server on application startup and keep it alive
+            let supervisor = Supervisor::new(Host::App(app.handle().clone()), logs, settings);
+
+            // Store supervisor in app state for cleanup on exit
+            app.manage(supervisor.clone());
+
+            // Stop the backend however the app goes away
+            shutdown::handle_termination_signals(app.handle());
+            shutdown::stop_backend_on_panic(supervisor.clone());
+
+            // Before any window can request files
+            asset_scope::init(app.handle());
+
+            // The main window only opens once the backend accepts requests
+            splash::open_main_when_ready(app.handle());
+            supervisor.start();
+
+            if let Err(e) = menu::install(app.handle()) {
+                log::error!("Failed to create the application menu: {}", e);
+            }
+
+            // `app <project> [--session <id>]`; on Windows and Linux links
+            // arrive as arguments too, forwarded by the running instance
+            let args: Vec<String> = std::env::args().skip(1).collect();
+            let cwd = std::env::current_dir().ok();
+            if let Some(request) = OpenRequest::from_args(&args, cwd.as_deref()) {
+                launch::open(app.handle(), request);
+            }
+            let handle = app.handle().clone();
+            app.deep_link().on_open_url(move |event| {
+                for url in event.urls() {
+                    if let Some(request) = OpenRequest::from_url(&url) {
+                        launch::open(&handle, request);
+                    }
                 }
-            };
+            });
+            // Installers register the scheme; this covers running unbundled
+            #[cfg(any(windows, target_os = "linux"))]
+            if let Err(e) = app.deep_link().register_all() {
+                log::warn!(
+                    "Failed to register the {} URL scheme: {}",
+                    launch::URL_SCHEME,
+                    e
+                );
+            }
 
-            // Store backend process in app state for cleanup on exit
-            app.manage(BackendProcess(Mutex::new(backend_process)));
+            // Status, background mode and quitting live in the tray
+            if let Err(e) = tray::create(app.handle()) {
+                log::error!("Failed to create the tray icon: {}", e);
+            }
 
             Ok(())
         })
+        .on_menu_event(menu::handle_event)
+        // A reload leaves the page's chats streaming to nobody
+        .on_page_load(|webview, payload| {
+            if payload.event() == PageLoadEvent::Started {
+                abandon_chats(webview.app_handle(), webview.label());
+            }
+        })
         .on_window_event(|window, event| {
-            if let tauri::WindowEvent::CloseRequested { .. } = event {
-                // Clean up backend process when window closes
-                let state = window.state::<BackendProcess>();
-                let mut process_guard = state.0.lock().unwrap();
-                if let Some(mut child) = process_guard.take() {
-                    println!("Stopping backend server...");
-                    let _ = child.kill();
-                    let _ = child.wait();
-                    println!("✓ Backend server stopped");
+            let store = window.state::<WindowStateStore>();
+            match event {
+                WindowEvent::Moved(_)
+                | WindowEvent::Resized(_)
+                | WindowEvent::CloseRequested { .. } => store.track(window),
+                WindowEvent::Destroyed => {
+                    store.closed(window);
+                    abandon_chats(window.app_handle(), window.label());
                 }
+                _ => {}
             }
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(context)
+        .expect("error while building tauri application");
+
+    app.run(|app, event| match event {
+        // The last window closed (an explicit exit carries a code); keep
+        // running in the tray if the user asked for that
+        RunEvent::ExitRequested {
+            code: None, api, ..
+        } if app
+            .state::<SettingsStore>()
+            .get()
+            .keep_running_in_background =>
+        {
+            api.prevent_exit();
+            app.state::<WindowStateStore>().save();
+            log::info!("Last window closed, backend keeps running in the background");
+        }
+        // Windows share the backend; Tauri only requests an exit once the
+        // last one has closed
+        RunEvent::ExitRequested { .. } | RunEvent::Exit => {
+            let store = app.state::<WindowStateStore>();
+            for window in app.webview_windows().values() {
+                store.track(&window.as_ref().window());
+            }
+            store.save();
+
+            // Hide remaining windows so the app doesn't look frozen while
+            // the backend drains
+            for window in app.webview_windows().values() {
+                let _ = window.hide();
+            }
+            app.state::<Supervisor>().shutdown();
+        }
+        _ => {}
+    });
+}
+
+// Stop streaming the chats of a webview that is gone or reloading
+fn abandon_chats(app: &AppHandle, label: &str) {
+    let base_url = app
+        .try_state::<Supervisor>()
+        .and_then(|supervisor| supervisor.base_url());
+    app.state::<ChatStreams>().abandon(base_url, label);
 }
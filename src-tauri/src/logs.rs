@@ -0,0 +1,279 @@
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use tauri::plugin::TauriPlugin;
+use tauri::Runtime;
+use tauri_plugin_log::{RotationStrategy, Target, TargetKind};
+use time::format_description::FormatItem;
+use time::macros::format_description;
+use time::OffsetDateTime;
+
+use crate::host::Host;
+
+// Event carrying each new backend output line, for a live tail in the UI
+pub const LOG_EVENT: &str = "backend://log";
+
+// Log target used for everything the backend prints
+pub const BACKEND_TARGET: &str = "backend";
+
+// Lines kept in memory for get_backend_logs
+const RING_CAPACITY: usize = 2000;
+
+// Log files under the app log directory rotate at this size, keeping a few
+const MAX_FILE_SIZE: u128 = 5 * 1024 * 1024;
+const KEPT_FILES: usize = 5;
+
+// `<name>.log`, rotated to `<name>_<date>.log`
+const LOG_FILE_NAME: &str = "claude-code";
+
+// The log plugin's record and rotated file name formats, in UTC
+const RECORD_DATE_FORMAT: &[FormatItem<'_>] =
+    format_description!("[[[year]-[month]-[day]][[[hour]:[minute]:[second]]");
+const FILE_DATE_FORMAT: &[FormatItem<'_>] =
+    format_description!("[year]-[month]-[day]_[hour]-[minute]-[second]");
+
+// Shell and backend logs go to stdout and to rotating files in the app log
+// directory (e.g. ~/.local/share/com.claude.code.webui/logs on Linux)
+pub fn plugin<R: Runtime>() -> TauriPlugin<R> {
+    tauri_plugin_log::Builder::new()
+        .targets([
+            Target::new(TargetKind::Stdout),
+            Target::new(TargetKind::LogDir {
+                file_name: Some(LOG_FILE_NAME.to_string()),
+            }),
+        ])
+        .level(log::LevelFilter::Info)
+        .max_file_size(MAX_FILE_SIZE)
+        .rotation_strategy(RotationStrategy::KeepSome(KEPT_FILES))
+        .build()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+// Payload of `backend://log`
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogLine {
+    pub seq: u64,
+    // Milliseconds since the Unix epoch
+    pub timestamp: u64,
+    pub stream: Stream,
+    pub text: String,
+}
+
+struct Ring {
+    lines: VecDeque<LogLine>,
+    next_seq: u64,
+}
+
+// Recent backend output, shared between the output readers and the commands
+#[derive(Clone)]
+pub struct BackendLogs {
+    host: Host,
+    ring: Arc<Mutex<Ring>>,
+}
+
+impl BackendLogs {
+    pub fn new(host: Host) -> Self {
+        Self {
+            host,
+            ring: Arc::new(Mutex::new(Ring {
+                lines: VecDeque::with_capacity(RING_CAPACITY),
+                next_seq: 0,
+            })),
+        }
+    }
+
+    pub fn push(&self, stream: Stream, text: &str) {
+        let level = match stream {
+            Stream::Stdout => log::Level::Info,
+            Stream::Stderr => log::Level::Warn,
+        };
+        log::log!(target: BACKEND_TARGET, level, "{}", text);
+
+        let line = {
+            let mut ring = self.ring.lock().unwrap();
+            let line = LogLine {
+                seq: ring.next_seq,
+                timestamp: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|elapsed| elapsed.as_millis() as u64)
+                    .unwrap_or_default(),
+                stream,
+                text: text.to_string(),
+            };
+            ring.next_seq += 1;
+            if ring.lines.len() == RING_CAPACITY {
+                ring.lines.pop_front();
+            }
+            ring.lines.push_back(line.clone());
+            line
+        };
+        self.host.emit(LOG_EVENT, line);
+    }
+
+    // The most recent `limit` lines (all kept lines by default), oldest first
+    pub fn tail(&self, limit: Option<usize>) -> Vec<LogLine> {
+        let ring = self.ring.lock().unwrap();
+        let skip = limit.map_or(0, |limit| ring.lines.len().saturating_sub(limit));
+        ring.lines.iter().skip(skip).cloned().collect()
+    }
+}
+
+// The log plugin needs a running app, so headless mode writes the same
+// rotating files, in the same format, through this
+pub struct LogFile {
+    dir: PathBuf,
+    max_size: u64,
+    file: Option<File>,
+    size: u64,
+}
+
+impl LogFile {
+    pub fn open(dir: PathBuf) -> io::Result<Self> {
+        Self::with_max_size(dir, MAX_FILE_SIZE as u64)
+    }
+
+    fn with_max_size(dir: PathBuf, max_size: u64) -> io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        let mut log_file = Self {
+            dir,
+            max_size,
+            file: None,
+            size: 0,
+        };
+        log_file.reopen()?;
+        Ok(log_file)
+    }
+
+    pub fn write(&mut self, record: &log::Record) {
+        let line = format!(
+            "{}[{}][{}] {}\n",
+            OffsetDateTime::now_utc()
+                .format(RECORD_DATE_FORMAT)
+                .unwrap_or_default(),
+            record.target(),
+            record.level(),
+            record.args()
+        );
+        if self.size != 0 && self.size + line.len() as u64 > self.max_size {
+            if let Err(e) = self.rotate() {
+                eprintln!("Failed to rotate the log file: {}", e);
+            }
+        }
+        if let Some(file) = &mut self.file {
+            if file.write_all(line.as_bytes()).is_ok() {
+                self.size += line.len() as u64;
+            }
+        }
+    }
+
+    fn path(&self) -> PathBuf {
+        self.dir.join(format!("{}.log", LOG_FILE_NAME))
+    }
+
+    fn reopen(&mut self) -> io::Result<()> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.path())?;
+        self.size = file.metadata()?.len();
+        self.file = Some(file);
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file = None;
+        let date = OffsetDateTime::now_utc()
+            .format(FILE_DATE_FORMAT)
+            .unwrap_or_default();
+        let rotated = self.dir.join(format!("{}_{}.log", LOG_FILE_NAME, date));
+        // Rotated twice within a second
+        if rotated.is_file() {
+            fs::rename(&rotated, rotated.with_extension("log.bak"))?;
+        }
+        fs::rename(self.path(), rotated)?;
+        self.remove_old_files()?;
+        self.reopen()
+    }
+
+    // Rotated files sort by date; like the plugin's KeepSome, keep the newest
+    // so that there are KEPT_FILES including the active one
+    fn remove_old_files(&self) -> io::Result<()> {
+        let prefix = format!("{}_", LOG_FILE_NAME);
+        let mut rotated: Vec<PathBuf> = fs::read_dir(&self.dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(&prefix))
+            })
+            .collect();
+        rotated.sort();
+        let excess = rotated.len().saturating_sub(KEPT_FILES - 1);
+        for path in &rotated[..excess] {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(log_file: &mut LogFile, message: &str) {
+        log_file.write(
+            &log::Record::builder()
+                .target(BACKEND_TARGET)
+                .level(log::Level::Info)
+                .args(format_args!("{}", message))
+                .build(),
+        );
+    }
+
+    #[test]
+    fn log_file_rotates_like_the_plugin() {
+        let dir = std::env::temp_dir().join(format!("claude-webui-logs-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        // Left by earlier runs
+        for day in 1..=6 {
+            let name = format!("claude-code_2000-01-0{}_00-00-00.log", day);
+            fs::write(dir.join(name), "old").unwrap();
+        }
+
+        let mut log_file = LogFile::with_max_size(dir.clone(), 100).unwrap();
+        write(&mut log_file, "first");
+        let active = fs::read_to_string(dir.join("claude-code.log")).unwrap();
+        assert!(active.ends_with("[backend][INFO] first\n"), "{}", active);
+
+        write(&mut log_file, &"x".repeat(80));
+        let active = fs::read_to_string(dir.join("claude-code.log")).unwrap();
+        assert!(!active.contains("first"));
+        assert!(active.ends_with(&format!("{}\n", "x".repeat(80))));
+
+        let mut rotated: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .filter(|name| name != "claude-code.log")
+            .collect();
+        rotated.sort();
+        assert_eq!(rotated.len(), KEPT_FILES - 1, "{:?}", rotated);
+        assert_eq!(rotated[0], "claude-code_2000-01-04_00-00-00.log");
+        let newest = fs::read_to_string(dir.join(rotated.last().unwrap())).unwrap();
+        assert!(newest.ends_with("first\n"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,220 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+use std::thread;
+
+use tauri::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::{AppHandle, Emitter, Manager, WebviewWindow, Wry};
+use tauri_plugin_dialog::DialogExt;
+
+use crate::asset_scope;
+use crate::settings::SettingsStore;
+use crate::windows;
+
+// Menu actions and their default accelerators; the settings' `shortcuts`
+// map overrides these by action, and an empty accelerator removes one
+const NEW_CHAT: &str = "new-chat";
+const OPEN_PROJECT: &str = "open-project";
+const NEW_WINDOW: &str = "new-window";
+const ABORT: &str = "abort";
+const EXPORT: &str = "export";
+const TOGGLE_DEVTOOLS: &str = "toggle-devtools";
+const RELOAD: &str = "reload";
+const OPEN_LOGS: &str = "open-logs";
+
+const DEFAULT_SHORTCUTS: &[(&str, &str)] = &[
+    (NEW_CHAT, "CmdOrCtrl+N"),
+    (OPEN_PROJECT, "CmdOrCtrl+O"),
+    (NEW_WINDOW, "CmdOrCtrl+Shift+N"),
+    (ABORT, "CmdOrCtrl+."),
+    (EXPORT, "CmdOrCtrl+Shift+E"),
+    (TOGGLE_DEVTOOLS, "CmdOrCtrl+Alt+I"),
+    (RELOAD, "CmdOrCtrl+R"),
+];
+
+// Actions the focused window's frontend carries out, as `menu://<action>`
+// events (see frontend/src/hooks/useDesktopMenu.ts)
+const FRONTEND_ACTIONS: &[&str] = &[NEW_CHAT, ABORT, EXPORT];
+
+// Payload: the chosen directory
+const OPEN_PROJECT_EVENT: &str = "menu://open-project";
+
+// Build the application menu from the current shortcut settings and install
+// it, replacing any previous one
+pub fn install(app: &AppHandle) -> tauri::Result<()> {
+    let shortcuts = app.state::<SettingsStore>().get().shortcuts;
+    app.set_menu(build(app, &shortcuts)?)?;
+    Ok(())
+}
+
+fn build(app: &AppHandle, shortcuts: &BTreeMap<String, String>) -> tauri::Result<Menu<Wry>> {
+    let item = |id: &str, text: &str| -> tauri::Result<MenuItem<Wry>> {
+        let accelerator = shortcuts
+            .get(id)
+            .map(String::as_str)
+            .or_else(|| {
+                DEFAULT_SHORTCUTS
+                    .iter()
+                    .find(|(action, _)| *action == id)
+                    .map(|(_, accelerator)| *accelerator)
+            })
+            .filter(|accelerator| !accelerator.is_empty());
+        MenuItem::with_id(app, id, text, true, accelerator).or_else(|e| {
+            log::warn!("Ignoring shortcut {:?} for {}: {}", accelerator, id, e);
+            MenuItem::with_id(app, id, text, true, None::<&str>)
+        })
+    };
+
+    let menu = Menu::new(app)?;
+    #[cfg(target_os = "macos")]
+    menu.append(&Submenu::with_items(
+        app,
+        &app.package_info().name,
+        true,
+        &[
+            &PredefinedMenuItem::about(app, None, None)?,
+            &PredefinedMenuItem::separator(app)?,
+            &PredefinedMenuItem::hide(app, None)?,
+            &PredefinedMenuItem::hide_others(app, None)?,
+            &PredefinedMenuItem::show_all(app, None)?,
+            &PredefinedMenuItem::separator(app)?,
+            &PredefinedMenuItem::quit(app, None)?,
+        ],
+    )?)?;
+    menu.append(&Submenu::with_items(
+        app,
+        "File",
+        true,
+        &[
+            &item(NEW_CHAT, "New Chat")?,
+            &item(OPEN_PROJECT, "Open Project...")?,
+            &item(NEW_WINDOW, "New Window")?,
+            &PredefinedMenuItem::separator(app)?,
+            &PredefinedMenuItem::close_window(app, None)?,
+        ],
+    )?)?;
+    // The webview relies on these for clipboard shortcuts on macOS
+    menu.append(&Submenu::with_items(
+        app,
+        "Edit",
+        true,
+        &[
+            &PredefinedMenuItem::undo(app, None)?,
+            &PredefinedMenuItem::redo(app, None)?,
+            &PredefinedMenuItem::separator(app)?,
+            &PredefinedMenuItem::cut(app, None)?,
+            &PredefinedMenuItem::copy(app, None)?,
+            &PredefinedMenuItem::paste(app, None)?,
+            &PredefinedMenuItem::select_all(app, None)?,
+        ],
+    )?)?;
+    menu.append(&Submenu::with_items(
+        app,
+        "Session",
+        true,
+        &[
+            &item(ABORT, "Abort Current Request")?,
+            &item(EXPORT, "Export Conversation...")?,
+        ],
+    )?)?;
+    let view = Submenu::with_items(app, "View", true, &[&item(RELOAD, "Reload")?])?;
+    #[cfg(any(debug_assertions, feature = "devtools"))]
+    view.append(&item(TOGGLE_DEVTOOLS, "Toggle Developer Tools")?)?;
+    menu.append(&view)?;
+    menu.append(&Submenu::with_items(
+        app,
+        "Help",
+        true,
+        &[&item(OPEN_LOGS, "Open Logs")?],
+    )?)?;
+    Ok(menu)
+}
+
+pub fn handle_event(app: &AppHandle, event: MenuEvent) {
+    let action = event.id().as_ref();
+    let window = focused_window(app);
+    match action {
+        _ if FRONTEND_ACTIONS.contains(&action) => {
+            if let Some(window) = window {
+                let _ = window.emit_to(window.label(), &format!("menu://{}", action), ());
+            }
+        }
+        NEW_WINDOW => {
+            if let Err(e) = windows::open(app, None, None) {
+                log::error!("Failed to open a new window: {}", e);
+            }
+        }
+        OPEN_PROJECT => open_project(app, window),
+        RELOAD => {
+            if let Some(window) = window {
+                let _ = window.reload();
+            }
+        }
+        #[cfg(any(debug_assertions, feature = "devtools"))]
+        TOGGLE_DEVTOOLS => {
+            if let Some(window) = window {
+                if window.is_devtools_open() {
+                    window.close_devtools();
+                } else {
+                    window.open_devtools();
+                }
+            }
+        }
+        OPEN_LOGS => match app.path().app_log_dir() {
+            Ok(dir) => {
+                if let Err(e) = reveal(&dir) {
+                    log::error!("Failed to open {}: {}", dir.display(), e);
+                }
+            }
+            Err(e) => log::error!("No log directory: {}", e),
+        },
+        _ => {}
+    }
+}
+
+// Menu events are app-wide; actions apply to the window the user is looking at
+fn focused_window(app: &AppHandle) -> Option<WebviewWindow> {
+    app.webview_windows()
+        .into_values()
+        .find(|window| window.is_focused().unwrap_or(false))
+}
+
+// Show the chosen project in the focused window, or in a new one if there is none
+fn open_project(app: &AppHandle, window: Option<WebviewWindow>) {
+    let handle = app.clone();
+    app.dialog()
+        .file()
+        .set_title("Select Project Folder")
+        .pick_folder(move |folder| {
+            let Some(path) = folder.and_then(|folder| folder.into_path().ok()) else {
+                return;
+            };
+            asset_scope::allow_project(&handle, &path);
+            let path = path.to_string_lossy().into_owned();
+            match window {
+                Some(window) => {
+                    let _ = window.emit_to(window.label(), OPEN_PROJECT_EVENT, path);
+                }
+                None => {
+                    if let Err(e) = windows::open(&handle, Some(&path), None) {
+                        log::error!("Failed to open {}: {}", path, e);
+                    }
+                }
+            }
+        });
+}
+
+// Open a directory in the platform's file manager
+fn reveal(dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let program = if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(windows) {
+        "explorer"
+    } else {
+        "xdg-open"
+    };
+    let mut child = Command::new(program).arg(dir).spawn()?;
+    thread::spawn(move || child.wait());
+    Ok(())
+}
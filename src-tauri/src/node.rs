@@ -0,0 +1,300 @@
+use std::cmp::Reverse;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::Serialize;
+
+use crate::process;
+
+// Explicit path to the node executable, tried before anything else
+pub const NODE_PATH_ENV: &str = "CLAUDE_WEBUI_NODE_PATH";
+
+// Matches "engines" in backend/package.json
+pub const MIN_NODE_VERSION: Version = Version(20, 0, 0);
+
+#[cfg(windows)]
+const NODE_EXECUTABLE: &str = "node.exe";
+
+#[cfg(not(windows))]
+const NODE_EXECUTABLE: &str = "node";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version(pub u32, pub u32, pub u32);
+
+impl Version {
+    // Parses `node --version` output such as "v20.11.1"
+    fn parse(text: &str) -> Option<Self> {
+        let mut parts = text.trim().trim_start_matches('v').splitn(3, '.');
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts.next().unwrap_or("0").parse().ok()?;
+        let patch = parts
+            .next()
+            .unwrap_or("0")
+            .split(|c: char| !c.is_ascii_digit())
+            .next()?
+            .parse()
+            .ok()?;
+        Some(Self(major, minor, patch))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.0, self.1, self.2)
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeRuntime {
+    pub path: PathBuf,
+    pub version: String,
+}
+
+impl NodeRuntime {
+    // Directory to put first on the backend's PATH, so that scripts like the
+    // Claude CLI (`#!/usr/bin/env node`) find the same runtime
+    #[cfg(not(feature = "sidecar"))]
+    pub fn bin_dir(&self) -> Option<&Path> {
+        self.path.parent()
+    }
+}
+
+// Reported to the UI when no usable Node.js runtime exists
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum NodeError {
+    #[serde(rename_all = "camelCase")]
+    NotFound { searched: Vec<PathBuf> },
+    #[serde(rename_all = "camelCase")]
+    TooOld {
+        path: PathBuf,
+        version: String,
+        required: String,
+    },
+}
+
+impl fmt::Display for NodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NodeError::NotFound { searched } => {
+                write!(f, "Node.js not found. Looked for:")?;
+                for path in searched {
+                    write!(f, "\n  {}", path.display())?;
+                }
+                Ok(())
+            }
+            NodeError::TooOld {
+                path,
+                version,
+                required,
+            } => write!(
+                f,
+                "Node.js {} at {} is too old, {} or newer is required",
+                version,
+                path.display(),
+                required
+            ),
+        }
+    }
+}
+
+impl std::error::Error for NodeError {}
+
+// Find a Node.js runtime that satisfies MIN_NODE_VERSION. GUI launches don't
+// source shell profiles, so besides PATH this looks where nvm, fnm, volta,
+// asdf and Homebrew install Node.
+pub fn discover() -> Result<NodeRuntime, NodeError> {
+    let configured = std::env::var_os(NODE_PATH_ENV).map(PathBuf::from);
+    let candidates = candidates(configured);
+
+    let mut too_old: Option<(PathBuf, Version)> = None;
+    for path in &candidates {
+        if !path.is_file() {
+            continue;
+        }
+        let Some(version) = probe_version(path) else {
+            continue;
+        };
+        if version >= MIN_NODE_VERSION {
+            log::info!("Using Node.js {} at {}", version, path.display());
+            return Ok(NodeRuntime {
+                path: path.clone(),
+                version: version.to_string(),
+            });
+        }
+        if too_old
+            .as_ref()
+            .map_or(true, |(_, newest)| version > *newest)
+        {
+            too_old = Some((path.clone(), version));
+        }
+    }
+
+    match too_old {
+        Some((path, version)) => Err(NodeError::TooOld {
+            path,
+            version: version.to_string(),
+            required: MIN_NODE_VERSION.to_string(),
+        }),
+        None => Err(NodeError::NotFound {
+            searched: candidates,
+        }),
+    }
+}
+
+fn probe_version(path: &Path) -> Option<Version> {
+    let mut command = Command::new(path);
+    command.arg("--version");
+    process::hide_console(&mut command);
+    let output = command.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Version::parse(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn candidates(configured: Option<PathBuf>) -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    candidates.extend(configured);
+
+    if let Some(path) = std::env::var_os("PATH") {
+        candidates.extend(std::env::split_paths(&path).map(|dir| dir.join(NODE_EXECUTABLE)));
+    }
+
+    let home = dirs::home_dir().unwrap_or_default();
+    let env_dir =
+        |name: &str, default: PathBuf| std::env::var_os(name).map(PathBuf::from).unwrap_or(default);
+
+    // volta and asdf shims resolve the project's pinned version themselves
+    let volta = env_dir("VOLTA_HOME", home.join(".volta"));
+    candidates.push(volta.join("bin").join(NODE_EXECUTABLE));
+    let asdf = env_dir("ASDF_DATA_DIR", home.join(".asdf"));
+    candidates.push(asdf.join("shims").join(NODE_EXECUTABLE));
+
+    #[cfg(not(windows))]
+    {
+        let nvm = env_dir("NVM_DIR", home.join(".nvm"));
+        candidates.extend(installed_versions(&nvm.join("versions/node"), "bin"));
+
+        let fnm_default = dirs::data_dir().unwrap_or_default().join("fnm");
+        let fnm = env_dir("FNM_DIR", fnm_default);
+        candidates.extend(installed_versions(
+            &fnm.join("node-versions"),
+            "installation/bin",
+        ));
+        candidates.extend(installed_versions(
+            &home.join(".local/share/fnm/node-versions"),
+            "installation/bin",
+        ));
+
+        candidates.extend(installed_versions(&asdf.join("installs/nodejs"), "bin"));
+
+        candidates.push(PathBuf::from("/opt/homebrew/bin/node"));
+        candidates.push(PathBuf::from("/usr/local/bin/node"));
+        candidates.push(PathBuf::from("/usr/bin/node"));
+    }
+
+    #[cfg(windows)]
+    {
+        let roaming = dirs::data_dir().unwrap_or_default();
+        let nvm = env_dir("NVM_HOME", roaming.join("nvm"));
+        candidates.extend(installed_versions(&nvm, ""));
+
+        let fnm = env_dir("FNM_DIR", roaming.join("fnm"));
+        candidates.extend(installed_versions(
+            &fnm.join("node-versions"),
+            "installation",
+        ));
+
+        if let Some(program_files) = std::env::var_os("ProgramFiles") {
+            candidates.push(PathBuf::from(program_files).join("nodejs\\node.exe"));
+        }
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    candidates.retain(|path| seen.insert(path.clone()));
+    candidates
+}
+
+// Node executables of a version manager's per-version directories
+// (named like "v20.11.1"), newest first
+fn installed_versions(root: &Path, bin_subdir: &str) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(root) else {
+        return Vec::new();
+    };
+    let mut versions: Vec<(Version, PathBuf)> = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let version = Version::parse(&entry.file_name().to_string_lossy())?;
+            let path = entry.path().join(bin_subdir).join(NODE_EXECUTABLE);
+            Some((version, path))
+        })
+        .collect();
+    versions.sort_by_key(|(version, _)| Reverse(*version));
+    versions.into_iter().map(|(_, path)| path).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_node_versions() {
+        assert_eq!(Version::parse("v20.11.1\n"), Some(Version(20, 11, 1)));
+        assert_eq!(Version::parse("20"), Some(Version(20, 0, 0)));
+        assert_eq!(Version::parse("v21.7"), Some(Version(21, 7, 0)));
+        assert_eq!(
+            Version::parse("v22.0.0-nightly20240101a1b2c3d"),
+            Some(Version(22, 0, 0))
+        );
+    }
+
+    #[test]
+    fn rejects_other_names() {
+        for name in [
+            "",
+            "default",
+            "system",
+            "lts-iron",
+            ".DS_Store",
+            "v",
+            "x20.1.0",
+        ] {
+            assert_eq!(Version::parse(name), None, "{:?}", name);
+        }
+    }
+
+    #[test]
+    fn lists_installed_versions_newest_first() {
+        let root =
+            std::env::temp_dir().join(format!("claude-webui-node-test-{}", std::process::id()));
+        for name in [
+            "v18.19.0",
+            "v20.11.1",
+            "v20.9.0",
+            "v22.0.0-nightly20240101",
+            "default",
+            "alias",
+        ] {
+            fs::create_dir_all(root.join(name)).unwrap();
+        }
+
+        let found = installed_versions(&root, "bin");
+        let _ = fs::remove_dir_all(&root);
+
+        let expected: Vec<PathBuf> = ["v22.0.0-nightly20240101", "v20.11.1", "v20.9.0", "v18.19.0"]
+            .iter()
+            .map(|name| root.join(name).join("bin").join(NODE_EXECUTABLE))
+            .collect();
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn missing_version_directory_has_no_versions() {
+        let root = std::env::temp_dir().join("claude-webui-node-test-missing");
+        assert!(installed_versions(&root, "bin").is_empty());
+    }
+}
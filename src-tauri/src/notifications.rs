@@ -0,0 +1,74 @@
+use std::path::Path;
+
+use tauri::{Manager, WebviewWindow};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::settings::SettingsStore;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChatOutcomeKind {
+    Completed,
+    Error,
+    PermissionRequired,
+}
+
+// How a chat request ended, as seen in its stream (see chat::OutcomeWatcher)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChatOutcome {
+    pub kind: ChatOutcomeKind,
+    pub project_path: Option<String>,
+    pub session_id: Option<String>,
+    // Error message, or the tool waiting for permission
+    pub detail: Option<String>,
+}
+
+// Native notifications for chat requests that end while nobody is looking.
+// Desktop notifications have no click callback, so clicking one does no more
+// than the platform does on its own; it never navigates the window.
+pub fn chat_outcome(window: &WebviewWindow, outcome: ChatOutcome) {
+    if window.is_focused().unwrap_or(false) {
+        return;
+    }
+    let app = window.app_handle();
+    if let Some(project) = &outcome.project_path {
+        if app
+            .state::<SettingsStore>()
+            .get()
+            .muted_projects
+            .contains(project)
+        {
+            return;
+        }
+    }
+
+    let project = outcome
+        .project_path
+        .as_deref()
+        .and_then(|path| Path::new(path).file_name())
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "Claude Code".to_string());
+    let (title, body) = match outcome.kind {
+        ChatOutcomeKind::Completed => (
+            format!("{}: Claude finished", project),
+            "The task is done.".to_string(),
+        ),
+        ChatOutcomeKind::Error => (
+            format!("{}: Claude ran into an error", project),
+            outcome
+                .detail
+                .clone()
+                .unwrap_or_else(|| "The request failed.".to_string()),
+        ),
+        ChatOutcomeKind::PermissionRequired => (
+            format!("{}: Claude needs permission", project),
+            match &outcome.detail {
+                Some(tool) => format!("Claude is waiting for permission to use {}.", tool),
+                None => "Claude is waiting for your approval.".to_string(),
+            },
+        ),
+    };
+
+    if let Err(e) = app.notification().builder().title(title).body(body).show() {
+        log::warn!("Failed to show notification: {}", e);
+    }
+}
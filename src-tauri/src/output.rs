@@ -0,0 +1,207 @@
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read};
+use std::process::Child;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::logs::{BackendLogs, Stream};
+
+// Number of stderr lines kept to explain why the backend failed
+const RECENT_ERROR_LINES: usize = 20;
+
+// Logged by backend/cli/node.ts once the Claude CLI has been validated
+const SERVER_STARTING_MARKER: &str = "Server starting on ";
+
+#[derive(Default)]
+struct State {
+    listening_on: Option<String>,
+    recent_errors: VecDeque<String>,
+}
+
+// Forwards the backend's stdout/stderr to the logs and remembers what it said
+#[derive(Clone)]
+pub struct BackendOutput {
+    logs: BackendLogs,
+    state: Arc<Mutex<State>>,
+}
+
+impl BackendOutput {
+    // Take over the piped stdio of a freshly spawned backend
+    pub fn attach(child: &mut Child, logs: BackendLogs) -> Self {
+        let output = Self {
+            logs,
+            state: Arc::default(),
+        };
+
+        if let Some(stdout) = child.stdout.take() {
+            let output = output.clone();
+            thread::spawn(move || output.pump(stdout, Stream::Stdout));
+        }
+        if let Some(stderr) = child.stderr.take() {
+            let output = output.clone();
+            thread::spawn(move || output.pump(stderr, Stream::Stderr));
+        }
+
+        output
+    }
+
+    // Address from the "Server starting on" line, once it has been logged
+    pub fn listening_on(&self) -> Option<String> {
+        self.state.lock().unwrap().listening_on.clone()
+    }
+
+    // Last lines the backend wrote to stderr, oldest first
+    pub fn recent_errors(&self) -> Option<String> {
+        let state = self.state.lock().unwrap();
+        if state.recent_errors.is_empty() {
+            return None;
+        }
+        Some(Vec::from(state.recent_errors.clone()).join("\n"))
+    }
+
+    fn pump(&self, source: impl Read, stream: Stream) {
+        read_lines(source, |line| {
+            self.logs.push(stream, line);
+            self.state.lock().unwrap().record(stream, line);
+        });
+    }
+}
+
+impl State {
+    fn record(&mut self, stream: Stream, line: &str) {
+        if let Some((_, rest)) = line.split_once(SERVER_STARTING_MARKER) {
+            self.listening_on = rest.split_whitespace().next().map(str::to_string);
+        }
+        if stream == Stream::Stderr && !line.is_empty() {
+            if self.recent_errors.len() == RECENT_ERROR_LINES {
+                self.recent_errors.pop_front();
+            }
+            self.recent_errors.push_back(line.to_string());
+        }
+    }
+}
+
+// Calls `on_line` with every line of `source`, uncolored and without its line ending
+fn read_lines(source: impl Read, mut on_line: impl FnMut(&str)) {
+    let mut reader = BufReader::new(source);
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        match reader.read_until(b'\n', &mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+        let line = strip_ansi(&String::from_utf8_lossy(&buf));
+        on_line(line.trim_end());
+    }
+}
+
+// LogTape's pretty formatter colors its output; drop the escape sequences
+fn strip_ansi(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            if chars.next() == Some('[') {
+                for c in chars.by_ref() {
+                    if c.is_ascii_alphabetic() {
+                        break;
+                    }
+                }
+            }
+            continue;
+        }
+        result.push(c);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // What LogTape's pretty formatter writes for `logger.cli.info(...)`
+    const STARTING_LINE: &str = "\x1b[2m2025-06-01 12:00:00.000 +00:00\x1b[0m \x1b[1m\x1b[32mINF\x1b[0m \x1b[2mcli\x1b[0m \u{1f680} Server starting on 127.0.0.1:41823\n";
+
+    // Hands out at most `chunk` bytes per read, like a pipe under load
+    struct Chunked<'a> {
+        data: &'a [u8],
+        chunk: usize,
+    }
+
+    impl Read for Chunked<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let len = self.chunk.min(buf.len()).min(self.data.len());
+            buf[..len].copy_from_slice(&self.data[..len]);
+            self.data = &self.data[len..];
+            Ok(len)
+        }
+    }
+
+    fn lines(data: &str, chunk: usize) -> Vec<String> {
+        let mut lines = Vec::new();
+        let source = Chunked {
+            data: data.as_bytes(),
+            chunk,
+        };
+        read_lines(source, |line| lines.push(line.to_string()));
+        lines
+    }
+
+    fn listening_on(line: &str) -> Option<String> {
+        let mut state = State::default();
+        state.record(Stream::Stdout, line);
+        state.listening_on
+    }
+
+    #[test]
+    fn strips_colors_from_backend_output() {
+        assert_eq!(
+            strip_ansi(STARTING_LINE).trim_end(),
+            "2025-06-01 12:00:00.000 +00:00 INF cli \u{1f680} Server starting on 127.0.0.1:41823"
+        );
+        assert_eq!(
+            strip_ansi("\x1b[38;2;255;0;0mred\x1b[0m plain"),
+            "red plain"
+        );
+    }
+
+    #[test]
+    fn reassembles_lines_split_across_reads() {
+        let data = format!("first\r\n{}last", STARTING_LINE);
+        for chunk in [1, 3, 7, 4096] {
+            let lines = lines(&data, chunk);
+            assert_eq!(lines.len(), 3, "chunk size {}", chunk);
+            assert_eq!(lines[0], "first");
+            assert!(lines[1].ends_with("Server starting on 127.0.0.1:41823"));
+            assert_eq!(lines[2], "last");
+        }
+    }
+
+    #[test]
+    fn extracts_the_listening_address() {
+        let line = lines(STARTING_LINE, 5).remove(0);
+        assert_eq!(listening_on(&line).as_deref(), Some("127.0.0.1:41823"));
+        assert_eq!(
+            listening_on("INF cli \u{1f680} Server starting on localhost:8080").as_deref(),
+            Some("localhost:8080")
+        );
+        assert_eq!(
+            listening_on("INF cli \u{1f680} Server starting on ::1:8080").as_deref(),
+            Some("::1:8080")
+        );
+        assert_eq!(listening_on("INF cli Validating Claude CLI"), None);
+    }
+
+    #[test]
+    fn keeps_only_recent_stderr_lines() {
+        let mut state = State::default();
+        state.record(Stream::Stdout, "ignored");
+        for i in 0..RECENT_ERROR_LINES + 5 {
+            state.record(Stream::Stderr, &format!("error {}", i));
+        }
+        state.record(Stream::Stderr, "");
+        assert_eq!(state.recent_errors.len(), RECENT_ERROR_LINES);
+        assert_eq!(state.recent_errors.front().unwrap(), "error 5");
+    }
+}
@@ -0,0 +1,14 @@
+use std::path::PathBuf;
+
+use tauri::{AppHandle, Manager};
+
+// Per-user directory for files that only matter while the app is running:
+// $XDG_RUNTIME_DIR/<identifier> on Linux, the app cache directory elsewhere
+pub fn runtime_dir(app: &AppHandle) -> Result<PathBuf, tauri::Error> {
+    let dir = match app.path().runtime_dir() {
+        Ok(dir) => dir.join(&app.config().identifier),
+        Err(_) => app.path().app_cache_dir()?,
+    };
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
@@ -0,0 +1,424 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+// How long a stale backend gets to exit after SIGTERM before it is killed
+const STALE_GRACE_PERIOD: Duration = Duration::from_secs(2);
+
+// Put the backend in its own process group so that the Claude CLI processes
+// it spawns can be signalled together with it, and on Linux have the kernel
+// kill it if the app dies without running its shutdown sequence. Note that
+// PR_SET_PDEATHSIG fires when the spawning *thread* exits, which is the
+// supervisor's watcher thread and outlives the child.
+#[cfg(unix)]
+pub fn configure(command: &mut Command) {
+    use std::os::unix::process::CommandExt;
+
+    command.process_group(0);
+
+    #[cfg(target_os = "linux")]
+    {
+        let parent = std::process::id() as libc::pid_t;
+        unsafe {
+            command.pre_exec(move || {
+                if libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGKILL) != 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                // The app may have died between fork and prctl
+                if libc::getppid() != parent {
+                    return Err(std::io::Error::other("parent process exited"));
+                }
+                Ok(())
+            });
+        }
+    }
+}
+
+#[cfg(not(unix))]
+pub fn configure(command: &mut Command) {
+    hide_console(command);
+}
+
+// Release builds use the Windows GUI subsystem, so every console program we
+// start would otherwise pop up a console window
+#[cfg(windows)]
+pub fn hide_console(command: &mut Command) {
+    use std::os::windows::process::CommandExt;
+
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+    command.creation_flags(CREATE_NO_WINDOW);
+}
+
+#[cfg(not(windows))]
+pub fn hide_console(_command: &mut Command) {}
+
+// Politely ask the backend and its children to exit; returns false where
+// that isn't possible. Windows has no equivalent of SIGTERM for processes
+// without a console, so there the tree can only be killed.
+#[cfg(unix)]
+pub fn terminate_tree(pid: u32) -> bool {
+    unsafe {
+        libc::kill(-(pid as libc::pid_t), libc::SIGTERM);
+    }
+    true
+}
+
+#[cfg(not(unix))]
+pub fn terminate_tree(_pid: u32) -> bool {
+    false
+}
+
+// Kill the backend and everything it spawned
+#[cfg(unix)]
+pub fn kill_tree(pid: u32) {
+    unsafe {
+        libc::kill(-(pid as libc::pid_t), libc::SIGKILL);
+    }
+}
+
+#[cfg(not(unix))]
+pub fn kill_tree(pid: u32) {
+    let mut command = Command::new("taskkill");
+    command.args(["/T", "/F", "/PID", &pid.to_string()]);
+    hide_console(&mut command);
+    let _ = command.status();
+}
+
+// Whether any process of the backend's group is still alive
+#[cfg(unix)]
+pub fn tree_alive(pid: u32) -> bool {
+    unsafe { libc::kill(-(pid as libc::pid_t), 0) == 0 }
+}
+
+// Windows has no process groups; the backend itself stands in for its tree
+#[cfg(not(unix))]
+pub fn tree_alive(pid: u32) -> bool {
+    process_alive(pid)
+}
+
+#[cfg(unix)]
+fn process_alive(pid: u32) -> bool {
+    let alive = unsafe { libc::kill(pid as libc::pid_t, 0) == 0 };
+    // EPERM: it exists but belongs to another user
+    alive || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(windows)]
+fn process_alive(pid: u32) -> bool {
+    use windows_sys::Win32::Foundation::{CloseHandle, STILL_ACTIVE};
+    use windows_sys::Win32::System::Threading::{
+        GetExitCodeProcess, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if handle.is_null() {
+            return false;
+        }
+        let mut exit_code = 0;
+        let queried = GetExitCodeProcess(handle, &mut exit_code) != 0;
+        CloseHandle(handle);
+        queried && exit_code == STILL_ACTIVE as u32
+    }
+}
+
+// Wait until the backend and its children are gone, reaping the direct
+// child on the way; returns false if they are still running at `deadline`
+pub fn wait_for_tree(child: &mut Child, deadline: Instant) -> bool {
+    let pid = child.id();
+    let mut exited = false;
+    while Instant::now() < deadline {
+        if !exited {
+            exited = !matches!(child.try_wait(), Ok(None));
+        }
+        if exited && !tree_alive(pid) {
+            return true;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    false
+}
+
+#[derive(Serialize, Deserialize)]
+struct PidFileContents {
+    pid: u32,
+    port: u16,
+    // The app or headless instance supervising the backend; files written
+    // by older versions don't record it
+    #[serde(default)]
+    owner: Option<u32>,
+}
+
+const PID_FILE_PREFIX: &str = "backend";
+const PID_FILE_EXTENSION: &str = "pid";
+
+// Records the running backend so that a later launch can clean up after a
+// crash of the app that skipped the shutdown sequence. Every instance (a
+// second desktop window after a failed instance lock, or any number of
+// headless runs) keeps its own file, named after its PID.
+pub struct PidFile {
+    dir: PathBuf,
+    path: PathBuf,
+}
+
+impl PidFile {
+    pub fn new(dir: PathBuf) -> Self {
+        let path = dir.join(format!(
+            "{}-{}.{}",
+            PID_FILE_PREFIX,
+            std::process::id(),
+            PID_FILE_EXTENSION
+        ));
+        Self { dir, path }
+    }
+
+    pub fn write(&self, pid: u32, port: u16) {
+        let contents = PidFileContents {
+            pid,
+            port,
+            owner: Some(std::process::id()),
+        };
+        let contents = serde_json::to_string(&contents).unwrap_or_default();
+        if let Err(e) = fs::write(&self.path, contents) {
+            log::error!("Failed to write {}: {}", self.path.display(), e);
+        }
+    }
+
+    pub fn remove(&self) {
+        let _ = fs::remove_file(&self.path);
+    }
+
+    // Terminate backends left behind by instances that are no longer running
+    pub fn reap_stale(&self) {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return;
+        };
+        for path in entries.filter_map(|entry| entry.ok().map(|entry| entry.path())) {
+            let is_pid_file = path
+                .extension()
+                .is_some_and(|ext| ext == PID_FILE_EXTENSION)
+                && path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .is_some_and(|stem| stem.starts_with(PID_FILE_PREFIX));
+            if is_pid_file {
+                reap(&path);
+            }
+        }
+    }
+}
+
+fn reap(path: &Path) {
+    let Some(stale) = fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| serde_json::from_str::<PidFileContents>(&contents).ok())
+    else {
+        let _ = fs::remove_file(path);
+        return;
+    };
+
+    if owner_running(stale.owner, std::process::id(), process_alive) {
+        return;
+    }
+
+    // The PID may have been reused since; only touch the process if it
+    // still looks like a backend we started
+    if command_line(stale.pid).is_some_and(|args| is_backend(&args, stale.port)) {
+        log::info!("Stopping stale backend (PID {})...", stale.pid);
+        if terminate_tree(stale.pid) {
+            let deadline = Instant::now() + STALE_GRACE_PERIOD;
+            while tree_alive(stale.pid) && Instant::now() < deadline {
+                thread::sleep(Duration::from_millis(100));
+            }
+        }
+        if tree_alive(stale.pid) {
+            kill_tree(stale.pid);
+        }
+    }
+    let _ = fs::remove_file(path);
+}
+
+// Whether the instance that wrote a PID file is still supervising its
+// backend. Our own PID there was left by an earlier process that had it.
+fn owner_running(owner: Option<u32>, current: u32, alive: impl Fn(u32) -> bool) -> bool {
+    owner.is_some_and(|owner| owner != current && alive(owner))
+}
+
+// Whether a command line is that of a backend started with `--port <port>`
+fn is_backend(args: &[String], port: u16) -> bool {
+    let port = port.to_string();
+    args.windows(2)
+        .any(|pair| pair[0] == "--port" && pair[1] == port)
+}
+
+#[cfg(target_os = "linux")]
+fn command_line(pid: u32) -> Option<Vec<String>> {
+    let raw = fs::read(format!("/proc/{}/cmdline", pid)).ok()?;
+    Some(
+        raw.split(|byte| *byte == 0)
+            .filter(|arg| !arg.is_empty())
+            .map(|arg| String::from_utf8_lossy(arg).into_owned())
+            .collect(),
+    )
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn command_line(pid: u32) -> Option<Vec<String>> {
+    let output = Command::new("ps")
+        .args(["-o", "command=", "-p", &pid.to_string()])
+        .output()
+        .ok()?;
+    let line = String::from_utf8_lossy(&output.stdout);
+    let args: Vec<String> = line.split_whitespace().map(str::to_string).collect();
+    (!args.is_empty()).then_some(args)
+}
+
+// wmic is no longer installed by default; CIM is available from Windows 8 on
+#[cfg(windows)]
+fn command_line(pid: u32) -> Option<Vec<String>> {
+    let query = format!(
+        "(Get-CimInstance Win32_Process -Filter 'ProcessId={}').CommandLine",
+        pid
+    );
+    let mut command = Command::new("powershell");
+    command.args(["-NoProfile", "-NonInteractive", "-Command", &query]);
+    hide_console(&mut command);
+    let output = command.output().ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let args = split_command_line(text.trim());
+    (!args.is_empty()).then_some(args)
+}
+
+// Splits a Windows command line the way CommandLineToArgvW does, so that
+// quoted paths with spaces stay one argument
+#[cfg(any(windows, test))]
+fn split_command_line(line: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                let mut backslashes = 1;
+                while chars.peek() == Some(&'\\') {
+                    chars.next();
+                    backslashes += 1;
+                }
+                in_arg = true;
+                if chars.peek() == Some(&'"') {
+                    // Backslashes only escape when they precede a quote
+                    current.extend(std::iter::repeat('\\').take(backslashes / 2));
+                    if backslashes % 2 == 1 {
+                        chars.next();
+                        current.push('"');
+                    }
+                } else {
+                    current.extend(std::iter::repeat('\\').take(backslashes));
+                }
+            }
+            '"' => {
+                in_arg = true;
+                if quoted && chars.peek() == Some(&'"') {
+                    // `""` inside quotes is a literal quote
+                    chars.next();
+                    current.push('"');
+                } else {
+                    quoted = !quoted;
+                }
+            }
+            c if c.is_whitespace() && !quoted => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            c => {
+                in_arg = true;
+                current.push(c);
+            }
+        }
+    }
+    if in_arg {
+        args.push(current);
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn recognizes_the_backend_by_its_port_argument() {
+        let command = args(&[
+            "node",
+            "/app/cli.js",
+            "--port",
+            "41823",
+            "--host",
+            "127.0.0.1",
+        ]);
+        assert!(is_backend(&command, 41823));
+        assert!(!is_backend(&command, 8080));
+        assert!(!is_backend(&args(&["node", "--inspect", "41823"]), 41823));
+        assert!(!is_backend(&args(&["node", "41823", "--port"]), 41823));
+        assert!(!is_backend(&[], 41823));
+    }
+
+    #[test]
+    fn recognizes_the_backend_in_a_quoted_windows_command_line() {
+        let line = r#""C:\Program Files\Claude Code WebUI\node.exe" "C:\Users\Jane Doe\App Data\cli.js" --port "41823" --host 127.0.0.1"#;
+        let command = split_command_line(line);
+        assert_eq!(
+            command,
+            args(&[
+                r"C:\Program Files\Claude Code WebUI\node.exe",
+                r"C:\Users\Jane Doe\App Data\cli.js",
+                "--port",
+                "41823",
+                "--host",
+                "127.0.0.1",
+            ])
+        );
+        assert!(is_backend(&command, 41823));
+    }
+
+    #[test]
+    fn splits_windows_command_lines_like_the_c_runtime() {
+        assert_eq!(split_command_line("  a   b\tc  "), args(&["a", "b", "c"]));
+        assert_eq!(split_command_line(r#"a "" b"#), args(&["a", "", "b"]));
+        assert_eq!(
+            split_command_line(r#"a\\b "c\\" d"#),
+            args(&[r"a\\b", r"c\", "d"])
+        );
+        assert_eq!(
+            split_command_line(r#"\"a\" "b \"c\"""#),
+            args(&[r#""a""#, r#"b "c""#])
+        );
+        assert_eq!(split_command_line(r#""a ""b"" c""#), args(&[r#"a "b" c"#]));
+        assert_eq!(
+            split_command_line(r#"pre"quoted part"post"#),
+            args(&["prequoted partpost"])
+        );
+        assert!(split_command_line("").is_empty());
+    }
+
+    #[test]
+    fn only_a_live_other_instance_owns_a_backend() {
+        let alive = |pid| pid == 100 || pid == 200;
+        assert!(owner_running(Some(100), 200, alive));
+        assert!(!owner_running(Some(200), 200, alive));
+        assert!(!owner_running(Some(300), 200, alive));
+        assert!(!owner_running(None, 200, alive));
+    }
+}
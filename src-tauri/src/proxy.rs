@@ -0,0 +1,122 @@
+use std::io::Read;
+use std::sync::OnceLock;
+use std::thread;
+use std::time::Duration;
+
+use tauri::http::header::{AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE, HOST, TRANSFER_ENCODING};
+use tauri::http::{Request, Response, StatusCode};
+use tauri::{AppHandle, Manager, UriSchemeResponder};
+
+use crate::auth;
+use crate::supervisor::Supervisor;
+
+// Custom protocol the webview reaches the backend's /api routes through, so
+// its base URL does not depend on the port picked at launch
+pub const SCHEME: &str = "backend";
+
+const API_PREFIX: &str = "/api/";
+
+// Streamed by the `chat` command instead, see below
+const CHAT_PATH: &str = "/api/chat";
+
+// The proxied routes answer from local files, or from the Anthropic API when
+// testing a configuration
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+// Origin of `SCHEME` as the webview sees it; Windows and Android serve custom
+// protocols as http://<scheme>.localhost
+pub fn origin() -> String {
+    if cfg!(any(windows, target_os = "android")) {
+        format!("http://{}.localhost", SCHEME)
+    } else {
+        format!("{}://localhost", SCHEME)
+    }
+}
+
+fn client() -> &'static reqwest::blocking::Client {
+    static CLIENT: OnceLock<reqwest::blocking::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        reqwest::blocking::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .unwrap_or_default()
+    })
+}
+
+// Forward a `backend://localhost/api/...` request to the supervised backend.
+// The blocking client gets a thread of its own per request, so a slow request
+// doesn't hold up the others.
+pub fn handle(app: &AppHandle, request: Request<Vec<u8>>, responder: UriSchemeResponder) {
+    let base_url = app.state::<Supervisor>().base_url();
+    thread::spawn(move || {
+        let response = match base_url {
+            Some(base_url) => forward(&base_url, request),
+            None => error(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "The backend server is not running.",
+            ),
+        };
+        responder.respond(response);
+    });
+}
+
+fn forward(base_url: &str, request: Request<Vec<u8>>) -> Response<Vec<u8>> {
+    let path = request
+        .uri()
+        .path_and_query()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_default();
+    if !path.starts_with(API_PREFIX) {
+        return error(StatusCode::NOT_FOUND, "Not found");
+    }
+    // Custom protocol responses carry a complete body; the protocol handler
+    // has no way to hand the webview a body in pieces, so an NDJSON stream
+    // would only arrive once the request ends. The `chat` command (chat.rs)
+    // streams chat requests line by line over an IPC channel instead.
+    if path.split('?').next() == Some(CHAT_PATH) {
+        return error(
+            StatusCode::BAD_REQUEST,
+            "Chat requests go through the `chat` command",
+        );
+    }
+
+    let (parts, body) = request.into_parts();
+    let mut headers = parts.headers;
+    headers.remove(HOST);
+    headers.remove(AUTHORIZATION);
+    let upstream = client().request(parts.method, format!("{}{}", base_url, path));
+    let upstream = auth::authorize(upstream).headers(headers).body(body).send();
+    let mut upstream = match upstream {
+        Ok(upstream) => upstream,
+        Err(e) => {
+            log::warn!("Proxying {} failed: {}", path, e);
+            return error(StatusCode::BAD_GATEWAY, &e.to_string());
+        }
+    };
+
+    let mut response = Response::builder().status(upstream.status());
+    for (name, value) in upstream.headers() {
+        // The body is handed over whole, not in the backend's framing
+        if name != TRANSFER_ENCODING && name != CONTENT_LENGTH {
+            response = response.header(name, value);
+        }
+    }
+
+    let mut body = Vec::new();
+    if let Err(e) = upstream.read_to_end(&mut body) {
+        log::warn!("Reading {} from the backend failed: {}", path, e);
+        return error(StatusCode::BAD_GATEWAY, &e.to_string());
+    }
+    response
+        .body(body)
+        .unwrap_or_else(|e| error(StatusCode::BAD_GATEWAY, &e.to_string()))
+}
+
+fn error(status: StatusCode, message: &str) -> Response<Vec<u8>> {
+    let mut response = Response::new(message.as_bytes().to_vec());
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, "text/plain; charset=utf-8".parse().unwrap());
+    response
+}
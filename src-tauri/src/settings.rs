@@ -0,0 +1,262 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use crate::host::Host;
+
+// Stored in the app config directory
+// (e.g. ~/.config/com.claude.code.webui/settings.json on Linux)
+const SETTINGS_FILE: &str = "settings.json";
+
+// Desktop settings, mapped onto the backend's command line (see
+// backend/cli/args.ts) and environment
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    // `--host`; the backend binds to 127.0.0.1 when unset
+    pub host: Option<String>,
+    // `--claude-path`, for Claude installs automatic detection doesn't find
+    pub claude_path: Option<String>,
+    // `--debug`
+    pub debug: bool,
+    // Extra environment variables, e.g. ANTHROPIC_API_KEY or HTTPS_PROXY
+    pub env: BTreeMap<String, String>,
+    // Keep the backend, and the requests it is running, alive in the tray
+    // when the last window closes
+    pub keep_running_in_background: bool,
+    // Menu accelerators by action (see menu.rs), e.g. "new-chat" ->
+    // "CmdOrCtrl+N"; an empty string removes the default
+    pub shortcuts: BTreeMap<String, String>,
+    // Projects (by path) whose chats raise no desktop notifications
+    pub muted_projects: BTreeSet<String>,
+}
+
+impl Settings {
+    // Whether switching from `self` to `other` needs a backend restart
+    pub fn affects_backend(&self, other: &Settings) -> bool {
+        self.host != other.host
+            || self.claude_path != other.claude_path
+            || self.debug != other.debug
+            || self.env != other.env
+    }
+
+    pub fn apply(&self, command: &mut Command) {
+        if let Some(host) = &self.host {
+            command.arg("--host").arg(host);
+        }
+        if let Some(claude_path) = &self.claude_path {
+            command.arg("--claude-path").arg(claude_path);
+        }
+        if self.debug {
+            command.arg("--debug");
+        }
+        command.envs(&self.env);
+    }
+
+    // Blank strings from form fields mean "unset"; values the backend would
+    // fail on are rejected
+    fn normalize(mut self) -> Result<Self, String> {
+        let blank_to_none = |value: Option<String>| {
+            value
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        self.host = blank_to_none(self.host);
+        self.claude_path = blank_to_none(self.claude_path);
+        // The backend exits at startup when it can't run the given path
+        if let Some(claude_path) = &self.claude_path {
+            let path = Path::new(claude_path);
+            if !path.is_absolute() || !path.is_file() {
+                return Err(format!("Claude CLI not found at {}", claude_path));
+            }
+        }
+        for name in self.env.keys() {
+            if name.is_empty() || name.contains('=') || name.contains('\0') {
+                return Err(format!("Invalid environment variable name: {:?}", name));
+            }
+        }
+        Ok(self)
+    }
+}
+
+// The settings file and its current contents, shared by the commands and
+// the supervisor
+#[derive(Clone)]
+pub struct SettingsStore {
+    path: Option<PathBuf>,
+    settings: Arc<Mutex<Settings>>,
+}
+
+impl SettingsStore {
+    // A missing or unreadable file leaves the defaults in place
+    pub fn load(host: &Host) -> Self {
+        let path = match host.config_dir() {
+            Ok(dir) => Some(dir.join(SETTINGS_FILE)),
+            Err(e) => {
+                log::error!("No config directory for the settings file: {}", e);
+                None
+            }
+        };
+
+        let settings = path
+            .as_ref()
+            .and_then(|path| match fs::read_to_string(path) {
+                Ok(contents) => match serde_json::from_str(&contents) {
+                    Ok(settings) => Some(settings),
+                    Err(e) => {
+                        log::warn!("Ignoring invalid {}: {}", path.display(), e);
+                        None
+                    }
+                },
+                Err(_) => None,
+            })
+            .unwrap_or_default();
+
+        Self {
+            path,
+            settings: Arc::new(Mutex::new(settings)),
+        }
+    }
+
+    pub fn get(&self) -> Settings {
+        self.settings.lock().unwrap().clone()
+    }
+
+    // Use `host` until the app exits, without saving it
+    pub fn override_host(&self, host: String) {
+        self.settings.lock().unwrap().host = Some(host);
+    }
+
+    // Persist `settings` and return them along with whether the backend
+    // needs a restart to pick them up
+    pub fn update(&self, settings: Settings) -> Result<(Settings, bool), String> {
+        let settings = settings.normalize()?;
+        let mut current = self.settings.lock().unwrap();
+
+        if let Some(path) = &self.path {
+            let contents = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+            }
+            // Write then rename so a crash never leaves a truncated file
+            let temp = path.with_extension("json.tmp");
+            fs::write(&temp, contents)
+                .and_then(|_| fs::rename(&temp, path))
+                .map_err(|e| format!("Failed to save {}: {}", path.display(), e))?;
+        }
+
+        let restart = current.affects_backend(&settings);
+        *current = settings.clone();
+        Ok((settings, restart))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn existing_file() -> String {
+        std::env::current_exe()
+            .unwrap()
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn backend_settings_need_a_restart() {
+        let base = Settings::default();
+        let changes = [
+            Settings {
+                host: Some("0.0.0.0".to_string()),
+                ..Settings::default()
+            },
+            Settings {
+                claude_path: Some(existing_file()),
+                ..Settings::default()
+            },
+            Settings {
+                debug: true,
+                ..Settings::default()
+            },
+            Settings {
+                env: BTreeMap::from([("HTTPS_PROXY".to_string(), "http://proxy".to_string())]),
+                ..Settings::default()
+            },
+        ];
+        for changed in changes {
+            assert!(base.affects_backend(&changed), "{:?}", changed);
+            assert!(changed.affects_backend(&base), "{:?}", changed);
+        }
+    }
+
+    #[test]
+    fn desktop_settings_keep_the_backend_running() {
+        let base = Settings::default();
+        let changed = Settings {
+            keep_running_in_background: true,
+            shortcuts: BTreeMap::from([("new-chat".to_string(), "CmdOrCtrl+N".to_string())]),
+            muted_projects: BTreeSet::from(["/home/user/project".to_string()]),
+            ..Settings::default()
+        };
+        assert!(!base.affects_backend(&changed));
+        assert!(!base.affects_backend(&base.clone()));
+    }
+
+    #[test]
+    fn blank_fields_are_unset() {
+        let settings = Settings {
+            host: Some("  ".to_string()),
+            claude_path: Some(String::new()),
+            ..Settings::default()
+        }
+        .normalize()
+        .unwrap();
+        assert_eq!(settings, Settings::default());
+
+        let settings = Settings {
+            host: Some(" 0.0.0.0\n".to_string()),
+            claude_path: Some(format!(" {} ", existing_file())),
+            ..Settings::default()
+        }
+        .normalize()
+        .unwrap();
+        assert_eq!(settings.host.as_deref(), Some("0.0.0.0"));
+        assert_eq!(settings.claude_path, Some(existing_file()));
+    }
+
+    #[test]
+    fn rejects_a_claude_path_that_cannot_run() {
+        let dir = std::env::temp_dir().to_string_lossy().into_owned();
+        let missing = std::env::temp_dir()
+            .join(format!("claude-webui-missing-{}", std::process::id()))
+            .to_string_lossy()
+            .into_owned();
+        for claude_path in ["claude", dir.as_str(), missing.as_str()] {
+            let settings = Settings {
+                claude_path: Some(claude_path.to_string()),
+                ..Settings::default()
+            };
+            assert!(settings.normalize().is_err(), "{}", claude_path);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_environment_variable_names() {
+        for name in ["", "A=B", "A\0B"] {
+            let settings = Settings {
+                env: BTreeMap::from([(name.to_string(), "value".to_string())]),
+                ..Settings::default()
+            };
+            assert!(settings.normalize().is_err(), "{:?}", name);
+        }
+        let settings = Settings {
+            env: BTreeMap::from([("ANTHROPIC_API_KEY".to_string(), "key".to_string())]),
+            ..Settings::default()
+        };
+        assert!(settings.normalize().is_ok());
+    }
+}
@@ -0,0 +1,151 @@
+use std::process::Child;
+use std::sync::OnceLock;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Manager};
+
+use crate::auth;
+use crate::process;
+use crate::supervisor::Supervisor;
+use crate::types::ActiveRequestsResponse;
+
+// How long the backend and the Claude processes it spawned get to exit after
+// SIGTERM before they are killed
+const DEFAULT_GRACE_PERIOD: Duration = Duration::from_secs(5);
+const GRACE_PERIOD_ENV: &str = "CLAUDE_WEBUI_SHUTDOWN_GRACE_SECS";
+
+// Timeout for each request made to the backend while shutting down
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+pub fn grace_period() -> Duration {
+    std::env::var(GRACE_PERIOD_ENV)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_GRACE_PERIOD)
+}
+
+// Abort running chat requests so Claude can finish writing its history,
+// then ask the backend to exit and kill it only if it doesn't
+pub fn stop_backend(mut child: Child, base_url: Option<&str>) {
+    // The blocking HTTP client must not run on an async runtime thread, and
+    // this may be called from a panic hook on any thread
+    let aborted = match base_url.map(str::to_string) {
+        Some(base_url) => thread::spawn(move || abort_active_requests(&base_url))
+            .join()
+            .unwrap_or(0),
+        None => 0,
+    };
+
+    // Where the backend can't be asked to exit (Windows), waiting only helps
+    // the aborted Claude processes finish writing their history
+    let asked_to_exit = process::terminate_tree(child.id());
+    if asked_to_exit || aborted > 0 {
+        let grace_period = grace_period();
+        if process::wait_for_tree(&mut child, Instant::now() + grace_period) {
+            return;
+        }
+        if asked_to_exit {
+            log::warn!(
+                "Backend did not exit within {}s, killing it",
+                grace_period.as_secs()
+            );
+        }
+    }
+
+    process::kill_tree(child.id());
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+fn client() -> &'static reqwest::blocking::Client {
+    static CLIENT: OnceLock<reqwest::blocking::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        reqwest::blocking::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .unwrap_or_default()
+    })
+}
+
+// IDs of the chat requests the backend is currently streaming
+pub fn active_requests(base_url: &str) -> Result<Vec<String>, reqwest::Error> {
+    auth::authorize(client().get(format!("{}/api/requests", base_url)))
+        .send()?
+        .error_for_status()?
+        .json::<ActiveRequestsResponse>()
+        .map(|active| active.request_ids)
+}
+
+// Returns how many requests were aborted
+pub fn abort_active_requests(base_url: &str) -> usize {
+    let request_ids = match active_requests(base_url) {
+        Ok(request_ids) => request_ids,
+        Err(e) => {
+            log::warn!("Failed to list active requests: {}", e);
+            return 0;
+        }
+    };
+    let mut aborted = 0;
+    for request_id in request_ids {
+        log::info!("Aborting request {}...", request_id);
+        match abort_request(base_url, &request_id) {
+            Ok(()) => aborted += 1,
+            Err(e) => log::warn!("Failed to abort request {}: {}", request_id, e),
+        }
+    }
+    aborted
+}
+
+// Ask the backend to abort one chat request; its stream then ends with an
+// `aborted` message
+pub fn abort_request(base_url: &str, request_id: &str) -> Result<(), reqwest::Error> {
+    auth::authorize(client().post(format!("{}/api/abort/{}", base_url, request_id)))
+        .send()?
+        .error_for_status()?;
+    Ok(())
+}
+
+// Run `on_signal` (once) when the app itself is asked to terminate
+#[cfg(unix)]
+pub fn on_termination_signal(on_signal: impl FnOnce() + Send + 'static) {
+    use signal_hook::consts::{SIGINT, SIGTERM};
+    use signal_hook::iterator::Signals;
+
+    let mut signals = match Signals::new([SIGINT, SIGTERM]) {
+        Ok(signals) => signals,
+        Err(e) => {
+            log::error!("Failed to install signal handlers: {}", e);
+            return;
+        }
+    };
+
+    thread::spawn(move || {
+        if let Some(signal) = signals.forever().next() {
+            log::info!("Received signal {}, shutting down...", signal);
+            on_signal();
+        }
+    });
+}
+
+#[cfg(not(unix))]
+pub fn on_termination_signal(_on_signal: impl FnOnce() + Send + 'static) {}
+
+// Stop the backend when the app itself is asked to terminate
+pub fn handle_termination_signals(app: &AppHandle) {
+    let app = app.clone();
+    on_termination_signal(move || {
+        app.state::<Supervisor>().shutdown();
+        app.exit(0);
+    });
+}
+
+// Make sure a panic anywhere in the shell doesn't leave the backend running
+pub fn stop_backend_on_panic(supervisor: Supervisor) {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        default_hook(info);
+        supervisor.shutdown_after_panic();
+    }));
+}
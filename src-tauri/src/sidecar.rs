@@ -0,0 +1,88 @@
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use sha2::{Digest, Sha256};
+use tauri_plugin_shell::ShellExt;
+
+use crate::backend::{self, SpawnError};
+use crate::host::Host;
+
+// Name of the pinned Node.js runtime in bundle.externalBin
+const NODE_SIDECAR: &str = "binaries/node";
+
+// Standalone backend bundle, relative to the resource directory
+const BUNDLE: &str = "sidecar/backend.mjs";
+
+// Embedded by build.rs from the checksum prepare-sidecar.js wrote
+const EXPECTED_SHA256: Option<&str> = option_env!("BACKEND_SIDECAR_SHA256");
+
+// Command running the standalone backend bundle on the bundled Node.js,
+// after making sure the bundle is the one this app was built with
+pub fn command(host: &Host) -> Result<Command, SpawnError> {
+    let bundle = host
+        .resource_dir()
+        .map_err(SpawnError::Sidecar)?
+        .join(BUNDLE);
+    verify_bundle(&bundle)?;
+
+    let mut command = match host.app() {
+        Some(app) => {
+            let sidecar = app
+                .shell()
+                .sidecar(NODE_SIDECAR)
+                .map_err(|e| SpawnError::Sidecar(e.to_string()))?;
+            Command::from(sidecar)
+        }
+        // The bundler puts external binaries next to the executable, without
+        // the target triple
+        None => {
+            let exe = std::env::current_exe().map_err(|e| SpawnError::Sidecar(e.to_string()))?;
+            let name = Path::new(NODE_SIDECAR)
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned();
+            let node = exe
+                .with_file_name(name)
+                .with_extension(std::env::consts::EXE_EXTENSION);
+            Command::new(node)
+        }
+    };
+
+    log::info!("Starting backend sidecar: {:?}", bundle);
+
+    // The Claude CLI (`#!/usr/bin/env node`) runs on the bundled runtime too
+    if let Some(bin_dir) = Path::new(command.get_program()).parent() {
+        let bin_dir = bin_dir.to_path_buf();
+        backend::prepend_path(&mut command, &bin_dir);
+    }
+    if let Some(dir) = bundle.parent() {
+        command.current_dir(dir);
+    }
+    command.arg(&bundle).stdin(Stdio::null());
+    Ok(command)
+}
+
+fn verify_bundle(bundle: &Path) -> Result<(), SpawnError> {
+    let Some(expected) = EXPECTED_SHA256 else {
+        return Err(SpawnError::Sidecar(
+            "this build has no backend checksum; run `npm run build:sidecar` before building"
+                .to_string(),
+        ));
+    };
+    let contents = fs::read(bundle)
+        .map_err(|e| SpawnError::Sidecar(format!("failed to read {}: {}", bundle.display(), e)))?;
+    let actual: String = Sha256::digest(&contents)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    if !actual.eq_ignore_ascii_case(expected) {
+        return Err(SpawnError::ChecksumMismatch {
+            path: bundle.to_path_buf(),
+            expected: expected.to_string(),
+            actual,
+        });
+    }
+    Ok(())
+}
@@ -0,0 +1,213 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Emitter, Manager, Wry};
+
+use crate::launch::{self, OpenRequest};
+use crate::paths;
+use crate::windows;
+
+// Event carrying the command line of a launch that found the app running
+pub const SECOND_INSTANCE_EVENT: &str = "app://second-instance";
+
+// The running instance may hold the lock but not be listening yet
+const CONNECT_ATTEMPTS: u32 = 20;
+const CONNECT_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+// A launch that connects but never sends its arguments is given up on
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Payload of `app://second-instance`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecondInstance {
+    pub args: Vec<String>,
+    pub cwd: Option<PathBuf>,
+}
+
+// Held for the lifetime of the primary instance; the OS releases the lock
+// when the process dies, however it dies
+struct InstanceLock(#[allow(dead_code)] File);
+
+// Plugins are set up before any window is created, so a second launch exits
+// here without flashing a splash screen or starting another backend
+pub fn plugin() -> TauriPlugin<Wry> {
+    tauri::plugin::Builder::new("single-instance")
+        .setup(|app, _| {
+            let dir = paths::runtime_dir(app)?;
+            match try_lock(&dir.join("instance.lock")) {
+                Ok(Some(lock)) => {
+                    app.manage(InstanceLock(lock));
+                    if let Err(e) = listen(app, &dir) {
+                        log::error!("Failed to listen for other instances: {}", e);
+                    }
+                }
+                Ok(None) => {
+                    let message = SecondInstance {
+                        args: std::env::args().skip(1).collect(),
+                        cwd: std::env::current_dir().ok(),
+                    };
+                    match forward(&dir, &message) {
+                        Ok(()) => {
+                            log::info!("Already running, handed over to the existing instance");
+                            std::process::exit(0);
+                        }
+                        // Better two instances than none at all
+                        Err(e) => log::error!("Failed to reach the running instance: {}", e),
+                    }
+                }
+                Err(e) => log::error!("Failed to take the instance lock: {}", e),
+            }
+            Ok(())
+        })
+        .build()
+}
+
+// Show what the second launch asked for, or just bring the existing window
+// to the front (back, in background mode), and tell the frontend about it
+fn activate(app: &AppHandle, message: SecondInstance) {
+    let request = OpenRequest::from_args(&message.args, message.cwd.as_deref());
+    let handle = app.clone();
+    let _ = app.run_on_main_thread(move || match request {
+        Some(request) => launch::open(&handle, request),
+        None => windows::reopen(&handle),
+    });
+    let _ = app.emit(SECOND_INSTANCE_EVENT, message);
+}
+
+fn handle_connection(app: &AppHandle, stream: impl std::io::Read) {
+    let mut line = String::new();
+    if let Err(e) = BufReader::new(stream).read_line(&mut line) {
+        log::warn!("Failed to read from another instance: {}", e);
+        return;
+    }
+    match serde_json::from_str::<SecondInstance>(&line) {
+        Ok(message) => {
+            log::info!("Another launch forwarded arguments: {:?}", message.args);
+            activate(app, message);
+        }
+        Err(e) => log::warn!("Ignoring invalid message from another instance: {}", e),
+    }
+}
+
+fn forward(dir: &Path, message: &SecondInstance) -> std::io::Result<()> {
+    let mut line = serde_json::to_string(message)?;
+    line.push('\n');
+
+    let mut attempt = 0;
+    loop {
+        match connect(dir) {
+            Ok(mut stream) => return stream.write_all(line.as_bytes()),
+            Err(e) if attempt + 1 >= CONNECT_ATTEMPTS => return Err(e),
+            Err(_) => {
+                attempt += 1;
+                thread::sleep(CONNECT_RETRY_DELAY);
+            }
+        }
+    }
+}
+
+// flock() rather than File::try_lock, which needs a newer Rust than our MSRV
+#[cfg(unix)]
+fn try_lock(path: &Path) -> std::io::Result<Option<File>> {
+    use std::os::unix::io::AsRawFd;
+
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path)?;
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } == 0 {
+        return Ok(Some(file));
+    }
+    let error = std::io::Error::last_os_error();
+    match error.raw_os_error() {
+        Some(libc::EWOULDBLOCK) => Ok(None),
+        _ => Err(error),
+    }
+}
+
+// Windows refuses to open a file another process holds without sharing
+#[cfg(windows)]
+fn try_lock(path: &Path) -> std::io::Result<Option<File>> {
+    use std::os::windows::fs::OpenOptionsExt;
+
+    const ERROR_SHARING_VIOLATION: i32 = 32;
+    match OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .share_mode(0)
+        .open(path)
+    {
+        Ok(file) => Ok(Some(file)),
+        Err(e) if e.raw_os_error() == Some(ERROR_SHARING_VIOLATION) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(unix)]
+fn listen(app: &AppHandle, dir: &Path) -> std::io::Result<()> {
+    use std::os::unix::net::UnixListener;
+
+    // Left behind by a previous instance that didn't exit cleanly
+    let path = dir.join("instance.sock");
+    let _ = fs::remove_file(&path);
+    let listener = UnixListener::bind(&path)?;
+
+    let app = app.clone();
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            // Each on its own thread, so one stalled launch can't hold up
+            // the next
+            let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+            let app = app.clone();
+            thread::spawn(move || handle_connection(&app, stream));
+        }
+    });
+    Ok(())
+}
+
+#[cfg(unix)]
+fn connect(dir: &Path) -> std::io::Result<std::os::unix::net::UnixStream> {
+    std::os::unix::net::UnixStream::connect(dir.join("instance.sock"))
+}
+
+// std has no local sockets on Windows; listen on loopback and publish the
+// port next to the lock instead
+#[cfg(windows)]
+fn listen(app: &AppHandle, dir: &Path) -> std::io::Result<()> {
+    use std::net::{Ipv4Addr, TcpListener};
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    fs::write(
+        dir.join("instance.port"),
+        listener.local_addr()?.port().to_string(),
+    )?;
+
+    let app = app.clone();
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+            let app = app.clone();
+            thread::spawn(move || handle_connection(&app, stream));
+        }
+    });
+    Ok(())
+}
+
+#[cfg(windows)]
+fn connect(dir: &Path) -> std::io::Result<std::net::TcpStream> {
+    use std::net::{Ipv4Addr, TcpStream};
+
+    let port: u16 = fs::read_to_string(dir.join("instance.port"))?
+        .trim()
+        .parse()
+        .map_err(|_| std::io::Error::other("invalid instance port"))?;
+    TcpStream::connect((Ipv4Addr::LOCALHOST, port))
+}
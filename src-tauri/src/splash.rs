@@ -0,0 +1,114 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use tauri::{AppHandle, Listener, Manager};
+
+use crate::diagnostics;
+use crate::proxy;
+use crate::supervisor::{BackendState, Supervisor, STATUS_EVENT};
+use crate::windows;
+
+pub const SPLASH_WINDOW: &str = "splash";
+pub const MAIN_WINDOW: &str = "main";
+
+// How long the splash screen waits before reporting that the backend never came up
+const READY_TIMEOUT: Duration = Duration::from_secs(45);
+
+// Keep the splash screen up until the backend is ready, then replace it with
+// the main window (declared with `"create": false` in tauri.conf.json)
+pub fn open_main_when_ready(app: &AppHandle) {
+    let opened = Arc::new(AtomicBool::new(false));
+
+    let handle = app.clone();
+    let opened_on_status = opened.clone();
+    app.listen(STATUS_EVENT, move |_| {
+        let status = handle.state::<Supervisor>().status();
+        match status.state {
+            BackendState::Ready if !opened_on_status.swap(true, Ordering::SeqCst) => {
+                let app = handle.clone();
+                let _ = handle.run_on_main_thread(move || open_main_windows(&app));
+            }
+            BackendState::Failed if !opened_on_status.load(Ordering::SeqCst) => {
+                show_error(&handle, &describe_failure(&handle));
+                if let Some(diagnosis) = &status.diagnosis {
+                    diagnostics::show_dialog(&handle, diagnosis);
+                }
+            }
+            _ => {}
+        }
+    });
+
+    let handle = app.clone();
+    thread::spawn(move || {
+        thread::sleep(READY_TIMEOUT);
+        if !opened.load(Ordering::SeqCst) {
+            show_error(&handle, &describe_failure(&handle));
+            let status = handle.state::<Supervisor>().status();
+            if status.state != BackendState::Failed {
+                let diagnosis = status.diagnosis.unwrap_or_else(|| {
+                    diagnostics::startup_failure("still starting", status.error.as_deref(), true)
+                });
+                diagnostics::show_dialog(&handle, &diagnosis);
+            }
+        }
+    });
+}
+
+fn open_main_windows(app: &AppHandle) {
+    if let Err(e) = windows::open_initial(app) {
+        log::error!("Failed to open the main window: {}", e);
+        show_error(app, &e);
+        return;
+    }
+
+    // destroy() skips CloseRequested, which nothing needs to see for the splash screen
+    if let Some(splash) = app.get_webview_window(SPLASH_WINDOW) {
+        let _ = splash.destroy();
+    }
+}
+
+// The webview reaches the backend through the proxy protocol, whatever port it
+// listens on; chat streams go through the `chat` command instead
+pub fn backend_url_script() -> String {
+    format!(
+        "window.__BACKEND_URL__ = {};",
+        serde_json::to_string(&proxy::origin()).unwrap_or_default()
+    )
+}
+
+fn describe_failure(app: &AppHandle) -> String {
+    let status = app.state::<Supervisor>().status();
+    let mut reason = match status.state {
+        _ if status.diagnosis.is_some() => status
+            .diagnosis
+            .as_ref()
+            .map(|diagnosis| format!("{}\n{}", diagnosis.summary, diagnosis.remediation))
+            .unwrap_or_default(),
+        BackendState::Failed => format!(
+            "The backend server kept crashing and was not restarted after {} attempts.",
+            status.restarts + 1
+        ),
+        _ => format!(
+            "The backend server did not become ready within {} seconds.",
+            READY_TIMEOUT.as_secs()
+        ),
+    };
+    if let Some(last_exit) = &status.last_exit {
+        reason.push_str(&format!("\nLast exit: {}", last_exit));
+    }
+    if let Some(error) = &status.error {
+        reason.push_str(&format!("\n\n{}", error));
+    }
+    reason
+}
+
+// splash.html exposes showError() to switch to its error screen
+fn show_error(app: &AppHandle, reason: &str) {
+    let Some(splash) = app.get_webview_window(SPLASH_WINDOW) else {
+        return;
+    };
+    let reason = serde_json::to_string(reason).unwrap_or_default();
+    let _ = splash.eval(format!("window.showError && window.showError({})", reason));
+}
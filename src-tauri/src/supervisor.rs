@@ -0,0 +1,581 @@
+use std::collections::VecDeque;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::process::{Child, ExitStatus};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::backend::{self, SpawnError};
+use crate::diagnostics::{self, Diagnosis};
+use crate::host::Host;
+use crate::logs::BackendLogs;
+use crate::output::BackendOutput;
+use crate::process::{self, PidFile};
+use crate::settings::SettingsStore;
+use crate::shutdown;
+
+// Event emitted to every window whenever the backend state changes
+pub const STATUS_EVENT: &str = "backend://status";
+
+// How often the watcher checks whether the child has exited
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+// A freshly spawned backend must accept connections within this time
+const READY_TIMEOUT: Duration = Duration::from_secs(30);
+const PROBE_TIMEOUT: Duration = Duration::from_millis(200);
+
+// Restart delay doubles after each crash, starting here and capped below
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+// A backend that stayed up this long is considered healthy again,
+// so the backoff and crash history start over
+const STABLE_UPTIME: Duration = Duration::from_secs(60);
+
+// Give up once the backend crashed this many times within the window
+const CRASH_LOOP_LIMIT: usize = 5;
+const CRASH_LOOP_WINDOW: Duration = Duration::from_secs(120);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackendState {
+    Starting,
+    Ready,
+    // Exited unexpectedly; restarts after `retryInMs`
+    Crashed,
+    // Crashed too often or cannot be started at all; not retried
+    Failed,
+    Stopped,
+}
+
+// Payload of `backend://status`
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendStatus {
+    pub state: BackendState,
+    pub pid: Option<u32>,
+    pub port: Option<u16>,
+    // Milliseconds since the current process was spawned
+    pub uptime_ms: Option<u64>,
+    pub restarts: u32,
+    pub last_exit: Option<String>,
+    pub error: Option<String>,
+    pub retry_in_ms: Option<u64>,
+    // Set while the backend fails to start, cleared once it is ready
+    pub diagnosis: Option<Diagnosis>,
+}
+
+impl BackendStatus {
+    fn new(state: BackendState) -> Self {
+        Self {
+            state,
+            pid: None,
+            port: None,
+            uptime_ms: None,
+            restarts: 0,
+            last_exit: None,
+            error: None,
+            retry_in_ms: None,
+            diagnosis: None,
+        }
+    }
+}
+
+struct Shared {
+    child: Mutex<Option<Child>>,
+    // PID of `child`, readable without taking the lock (0 when none)
+    pid: AtomicU32,
+    // When `child` was spawned; stale once `pid` is 0
+    started_at: Mutex<Option<Instant>>,
+    status: Mutex<BackendStatus>,
+    port: Mutex<Option<u16>>,
+    // `--host` the running backend was started with
+    host: Mutex<Option<String>>,
+    stopping: Mutex<bool>,
+    wake: Condvar,
+}
+
+impl Shared {
+    fn is_stopping(&self) -> bool {
+        *self.stopping.lock().unwrap()
+    }
+
+    // Sleep for `duration`, returning early with `true` if a stop was requested
+    fn sleep_unless_stopped(&self, duration: Duration) -> bool {
+        let guard = self.stopping.lock().unwrap();
+        let (guard, _) = self
+            .wake
+            .wait_timeout_while(guard, duration, |stopping| !*stopping)
+            .unwrap();
+        *guard
+    }
+}
+
+// Keeps the Node.js backend running, restarting it with exponential backoff
+// when it exits unexpectedly
+#[derive(Clone)]
+pub struct Supervisor {
+    host: Host,
+    logs: BackendLogs,
+    settings: SettingsStore,
+    shared: Arc<Shared>,
+    pid_file: Option<Arc<PidFile>>,
+    // Set in headless mode, where clients are pointed at a known port
+    fixed_port: Option<u16>,
+    watcher: Arc<Mutex<Option<JoinHandle<()>>>>,
+}
+
+impl Supervisor {
+    pub fn new(host: Host, logs: BackendLogs, settings: SettingsStore) -> Self {
+        let pid_file = match host.runtime_dir() {
+            Ok(dir) => Some(Arc::new(PidFile::new(dir))),
+            Err(e) => {
+                log::error!("No runtime directory for the backend PID file: {}", e);
+                None
+            }
+        };
+
+        Self {
+            host,
+            logs,
+            settings,
+            pid_file,
+            fixed_port: None,
+            shared: Arc::new(Shared {
+                child: Mutex::new(None),
+                pid: AtomicU32::new(0),
+                started_at: Mutex::new(None),
+                status: Mutex::new(BackendStatus::new(BackendState::Stopped)),
+                port: Mutex::new(None),
+                host: Mutex::new(None),
+                stopping: Mutex::new(false),
+                wake: Condvar::new(),
+            }),
+            watcher: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    // Always listen on `port`, even if that means failing to start
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.fixed_port = Some(port);
+        self
+    }
+
+    // Spawn the backend and start watching it in a background thread
+    pub fn start(&self) {
+        let mut watcher = self.watcher.lock().unwrap();
+        if watcher.as_ref().is_some_and(|handle| !handle.is_finished()) {
+            return;
+        }
+        *self.shared.stopping.lock().unwrap() = false;
+
+        let supervisor = self.clone();
+        *watcher = Some(thread::spawn(move || supervisor.supervise()));
+    }
+
+    // Stop supervising and terminate the backend gracefully
+    pub fn shutdown(&self) {
+        *self.shared.stopping.lock().unwrap() = true;
+        self.shared.wake.notify_all();
+
+        let child = self.shared.child.lock().unwrap().take();
+        if let Some(child) = child {
+            log::info!("Stopping backend server...");
+            shutdown::stop_backend(child, self.base_url().as_deref());
+            self.forget_child();
+            log::info!("Backend server stopped");
+        }
+
+        let watcher = self.watcher.lock().unwrap().take();
+        if let Some(handle) = watcher {
+            if handle.thread().id() != thread::current().id() {
+                let _ = handle.join();
+            }
+        }
+    }
+
+    // Stop the backend and start it again, e.g. to apply new settings
+    pub fn restart(&self) {
+        log::info!("Restarting backend...");
+        self.shutdown();
+        self.shared.status.lock().unwrap().restarts += 1;
+        self.start();
+    }
+
+    // Panic hooks run before unwinding, possibly while this thread holds one
+    // of our locks, so never block on them here
+    pub fn shutdown_after_panic(&self) {
+        if let Ok(mut stopping) = self.shared.stopping.try_lock() {
+            *stopping = true;
+        }
+        self.shared.wake.notify_all();
+
+        let child = match self.shared.child.try_lock() {
+            Ok(mut child) => child.take(),
+            Err(_) => None,
+        };
+        match child {
+            Some(child) => {
+                let host = self
+                    .shared
+                    .host
+                    .try_lock()
+                    .ok()
+                    .and_then(|host| host.clone());
+                let base_url = self
+                    .shared
+                    .port
+                    .try_lock()
+                    .ok()
+                    .and_then(|port| *port)
+                    .map(|port| format!("http://{}:{}", connect_host(host.as_deref()), port));
+                shutdown::stop_backend(child, base_url.as_deref());
+            }
+            None => match self.shared.pid.load(Ordering::SeqCst) {
+                0 => {}
+                pid => process::kill_tree(pid),
+            },
+        }
+        self.forget_child();
+    }
+
+    pub fn status(&self) -> BackendStatus {
+        let mut status = self.shared.status.lock().unwrap().clone();
+        let running = self.shared.pid.load(Ordering::SeqCst) != 0;
+        status.uptime_ms = self
+            .shared
+            .started_at
+            .lock()
+            .unwrap()
+            .filter(|_| running)
+            .map(|started_at| started_at.elapsed().as_millis() as u64);
+        status
+    }
+
+    // Base URL the webview uses to reach the backend
+    pub fn base_url(&self) -> Option<String> {
+        let port = (*self.shared.port.lock().unwrap())?;
+        let host = self.shared.host.lock().unwrap();
+        Some(format!("http://{}:{}", connect_host(host.as_deref()), port))
+    }
+
+    // Keep the same port across restarts so the webview's base URL stays valid,
+    // unless something else grabbed it in the meantime
+    fn allocate_port(&self) -> Result<u16, std::io::Error> {
+        let mut port = self.shared.port.lock().unwrap();
+        if let Some(fixed) = self.fixed_port {
+            *port = Some(fixed);
+            return Ok(fixed);
+        }
+        match *port {
+            Some(current) if backend::is_port_free(current) => Ok(current),
+            previous => {
+                let next = backend::pick_free_port()?;
+                if let Some(previous) = previous {
+                    log::warn!("Port {} is taken, moving backend to {}", previous, next);
+                }
+                *port = Some(next);
+                Ok(next)
+            }
+        }
+    }
+
+    fn supervise(&self) {
+        if let Some(pid_file) = &self.pid_file {
+            pid_file.reap_stale();
+        }
+
+        let mut backoff = INITIAL_BACKOFF;
+        let mut crashes: VecDeque<Instant> = VecDeque::new();
+        // Restarts count across stop/start cycles of the same app run
+        let mut status = BackendStatus {
+            restarts: self.shared.status.lock().unwrap().restarts,
+            ..BackendStatus::new(BackendState::Starting)
+        };
+
+        loop {
+            status.state = BackendState::Starting;
+            status.pid = None;
+            status.retry_in_ms = None;
+            self.publish(&status);
+
+            let spawned = self
+                .allocate_port()
+                .map_err(SpawnError::from)
+                .and_then(|port| {
+                    status.port = Some(port);
+                    let settings = self.settings.get();
+                    *self.shared.host.lock().unwrap() = settings.host.clone();
+                    backend::spawn(&self.host, port, &settings)
+                });
+            match spawned {
+                Ok(mut child) => {
+                    let output = BackendOutput::attach(&mut child, self.logs.clone());
+                    let started_at = Instant::now();
+                    status.pid = Some(child.id());
+                    self.track_child(child, status.port.unwrap_or_default());
+
+                    let mut timed_out = false;
+                    let mut ready = false;
+                    let exit = match self.wait_until_ready(&output, started_at) {
+                        Watch::Ready => {
+                            ready = true;
+                            log::info!("Backend server started successfully");
+                            status.state = BackendState::Ready;
+                            status.error = None;
+                            status.diagnosis = None;
+                            self.publish(&status);
+                            match self.wait_for_exit() {
+                                Some(exit) => describe_exit(exit),
+                                None => break,
+                            }
+                        }
+                        Watch::Exited(exit) => describe_exit(exit),
+                        Watch::TimedOut => {
+                            timed_out = true;
+                            self.kill_child();
+                            format!("not ready after {}s", READY_TIMEOUT.as_secs())
+                        }
+                        Watch::Stopped => break,
+                    };
+                    log::error!("Backend server exited unexpectedly: {}", exit);
+                    status.error = output.recent_errors();
+                    status.last_exit = Some(exit);
+                    if !ready {
+                        let diagnosis = diagnostics::startup_failure(
+                            status.last_exit.as_deref().unwrap_or_default(),
+                            status.error.as_deref(),
+                            timed_out,
+                        );
+                        log::error!("Backend failed to start: {}", diagnosis.summary);
+                        // A missing Claude CLI, say, doesn't appear by retrying
+                        let permanent = diagnosis.is_permanent();
+                        status.diagnosis = Some(diagnosis);
+                        if permanent {
+                            status.pid = None;
+                            status.state = BackendState::Failed;
+                            self.publish(&status);
+                            return;
+                        }
+                    }
+
+                    if started_at.elapsed() >= STABLE_UPTIME {
+                        backoff = INITIAL_BACKOFF;
+                        crashes.clear();
+                    }
+                }
+                Err(e) => {
+                    log::error!("Failed to start backend: {}", e);
+                    status.last_exit = None;
+                    status.error = Some(e.to_string());
+                    status.diagnosis = Some(diagnostics::spawn_failure(&e));
+                    if !e.is_retryable() {
+                        status.state = BackendState::Failed;
+                        self.publish(&status);
+                        return;
+                    }
+                }
+            }
+            status.pid = None;
+
+            if self.shared.is_stopping() {
+                break;
+            }
+
+            let now = Instant::now();
+            crashes.push_back(now);
+            while crashes
+                .front()
+                .is_some_and(|crash| now.duration_since(*crash) > CRASH_LOOP_WINDOW)
+            {
+                crashes.pop_front();
+            }
+            if crashes.len() >= CRASH_LOOP_LIMIT {
+                log::error!(
+                    "Backend crashed {} times within {}s, giving up",
+                    crashes.len(),
+                    CRASH_LOOP_WINDOW.as_secs()
+                );
+                status.state = BackendState::Failed;
+                self.publish(&status);
+                return;
+            }
+
+            log::info!("Restarting backend in {}ms...", backoff.as_millis());
+            status.state = BackendState::Crashed;
+            status.retry_in_ms = Some(backoff.as_millis() as u64);
+            self.publish(&status);
+            if self.shared.sleep_unless_stopped(backoff) {
+                break;
+            }
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+            status.restarts += 1;
+        }
+
+        self.stop_untracked_child();
+        status.state = BackendState::Stopped;
+        status.pid = None;
+        status.retry_in_ms = None;
+        self.publish(&status);
+    }
+
+    // Wait for the backend to log its address and accept connections on it
+    fn wait_until_ready(&self, output: &BackendOutput, started_at: Instant) -> Watch {
+        loop {
+            match self.try_reap() {
+                Reap::Running => {}
+                Reap::Exited(exit) => return Watch::Exited(exit),
+                Reap::Gone => return Watch::Stopped,
+            }
+            if output
+                .listening_on()
+                .is_some_and(|address| accepts_connections(&address))
+            {
+                return Watch::Ready;
+            }
+            if started_at.elapsed() >= READY_TIMEOUT {
+                return Watch::TimedOut;
+            }
+            if self.shared.sleep_unless_stopped(POLL_INTERVAL) {
+                return Watch::Stopped;
+            }
+        }
+    }
+
+    // Block until the child exits, or return `None` once a stop was requested
+    fn wait_for_exit(&self) -> Option<ExitStatus> {
+        loop {
+            match self.try_reap() {
+                Reap::Running => {}
+                Reap::Exited(exit) => return Some(exit),
+                Reap::Gone => return None,
+            }
+            if self.shared.sleep_unless_stopped(POLL_INTERVAL) {
+                return None;
+            }
+        }
+    }
+
+    fn try_reap(&self) -> Reap {
+        let mut guard = self.shared.child.lock().unwrap();
+        let Some(child) = guard.as_mut() else {
+            return Reap::Gone;
+        };
+        match child.try_wait() {
+            Ok(Some(exit)) => {
+                // Claude CLI processes outlive a crashed backend otherwise
+                process::kill_tree(child.id());
+                guard.take();
+                self.forget_child();
+                Reap::Exited(exit)
+            }
+            Ok(None) => Reap::Running,
+            Err(e) => {
+                log::warn!("Failed to poll backend process: {}", e);
+                Reap::Running
+            }
+        }
+    }
+
+    fn kill_child(&self) {
+        if let Some(mut child) = self.shared.child.lock().unwrap().take() {
+            process::kill_tree(child.id());
+            let _ = child.kill();
+            let _ = child.wait();
+            self.forget_child();
+        }
+    }
+
+    // A stop that lands between spawning the backend and tracking it finds no
+    // child to stop, leaving that to the watcher on its way out
+    fn stop_untracked_child(&self) {
+        let child = self.shared.child.lock().unwrap().take();
+        if let Some(child) = child {
+            log::info!("Stopping backend started during shutdown...");
+            shutdown::stop_backend(child, self.base_url().as_deref());
+            self.forget_child();
+        }
+    }
+
+    fn track_child(&self, child: Child, port: u16) {
+        self.shared.pid.store(child.id(), Ordering::SeqCst);
+        *self.shared.started_at.lock().unwrap() = Some(Instant::now());
+        if let Some(pid_file) = &self.pid_file {
+            pid_file.write(child.id(), port);
+        }
+        *self.shared.child.lock().unwrap() = Some(child);
+    }
+
+    fn forget_child(&self) {
+        self.shared.pid.store(0, Ordering::SeqCst);
+        if let Some(pid_file) = &self.pid_file {
+            pid_file.remove();
+        }
+    }
+
+    fn publish(&self, status: &BackendStatus) {
+        *self.shared.status.lock().unwrap() = status.clone();
+        self.host.emit(STATUS_EVENT, self.status());
+    }
+}
+
+enum Reap {
+    Running,
+    Exited(ExitStatus),
+    Gone,
+}
+
+enum Watch {
+    Ready,
+    Exited(ExitStatus),
+    TimedOut,
+    Stopped,
+}
+
+// The backend may bind a wildcard address, which is reachable via loopback
+fn accepts_connections(address: &str) -> bool {
+    let Some((host, port)) = address.rsplit_once(':') else {
+        return false;
+    };
+    let host = match host.trim_matches(['[', ']']) {
+        "0.0.0.0" | "" => "127.0.0.1",
+        "::" => "::1",
+        host => host,
+    };
+    let Ok(port) = port.parse::<u16>() else {
+        return false;
+    };
+    let Ok(addrs) = (host, port).to_socket_addrs() else {
+        return false;
+    };
+    addrs
+        .into_iter()
+        .any(|addr| TcpStream::connect_timeout(&addr, PROBE_TIMEOUT).is_ok())
+}
+
+// Host to reach a backend started with `--host <host>` from this machine
+fn connect_host(host: Option<&str>) -> String {
+    match host.map(|host| host.trim_matches(['[', ']'])) {
+        None | Some("" | "0.0.0.0" | "localhost") => "127.0.0.1".to_string(),
+        Some("::") => "[::1]".to_string(),
+        Some(host) if host.contains(':') => format!("[{}]", host),
+        Some(host) => host.to_string(),
+    }
+}
+
+fn describe_exit(status: ExitStatus) -> String {
+    if let Some(code) = status.code() {
+        return format!("exit code {}", code);
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return format!("killed by signal {}", signal);
+        }
+    }
+    status.to_string()
+}
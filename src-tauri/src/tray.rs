@@ -0,0 +1,140 @@
+use std::thread::{self, ThreadId};
+
+use tauri::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Listener, Manager, Wry};
+
+use crate::chat::{self, ChatStreams};
+use crate::shutdown;
+use crate::supervisor::{BackendState, Supervisor, STATUS_EVENT};
+use crate::windows;
+
+const OPEN_ID: &str = "open";
+const RESTART_ID: &str = "restart";
+const ABORT_ID: &str = "abort";
+const QUIT_ID: &str = "quit";
+
+// Menu items whose text follows the backend. Setting menu text waits for
+// the main thread, while the events they follow are emitted from the
+// supervisor's and the chat streams' threads, which the main thread may in
+// turn be waiting for (the supervisor's shutdown joins its watcher on exit).
+// Updates are therefore posted to the main thread rather than made in the
+// listeners.
+#[derive(Clone)]
+struct StatusItems {
+    status: MenuItem<Wry>,
+    requests: MenuItem<Wry>,
+    abort: MenuItem<Wry>,
+    main_thread: ThreadId,
+}
+
+impl StatusItems {
+    fn show_state(&self, state: BackendState) {
+        self.assert_main_thread();
+        let text = match state {
+            BackendState::Starting => "Backend: starting",
+            BackendState::Ready => "Backend: running",
+            BackendState::Crashed => "Backend: restarting after a crash",
+            BackendState::Failed => "Backend: failed to start",
+            BackendState::Stopped => "Backend: stopped",
+        };
+        let _ = self.status.set_text(text);
+    }
+
+    fn show_requests(&self, count: usize) {
+        self.assert_main_thread();
+        let text = match count {
+            0 => "No active requests".to_string(),
+            1 => "1 active request".to_string(),
+            count => format!("{} active requests", count),
+        };
+        let _ = self.requests.set_text(text);
+        let _ = self.abort.set_enabled(count > 0);
+    }
+
+    fn assert_main_thread(&self) {
+        debug_assert_eq!(
+            thread::current().id(),
+            self.main_thread,
+            "tray items updated off the main thread"
+        );
+    }
+}
+
+// Tray icon that keeps the app reachable while it runs without windows; must
+// be called on the main thread
+pub fn create(app: &AppHandle) -> tauri::Result<()> {
+    let items = StatusItems {
+        status: MenuItem::with_id(app, "status", "Backend: starting", false, None::<&str>)?,
+        requests: MenuItem::with_id(app, "requests", "No active requests", false, None::<&str>)?,
+        abort: MenuItem::with_id(app, ABORT_ID, "Abort All Requests", false, None::<&str>)?,
+        main_thread: thread::current().id(),
+    };
+    let menu = Menu::with_items(
+        app,
+        &[
+            &items.status,
+            &items.requests,
+            &PredefinedMenuItem::separator(app)?,
+            &MenuItem::with_id(app, OPEN_ID, "Open Window", true, None::<&str>)?,
+            &MenuItem::with_id(app, RESTART_ID, "Restart Backend", true, None::<&str>)?,
+            &items.abort,
+            &PredefinedMenuItem::separator(app)?,
+            &MenuItem::with_id(app, QUIT_ID, "Quit", true, None::<&str>)?,
+        ],
+    )?;
+
+    let mut tray = TrayIconBuilder::with_id("main")
+        .tooltip("Claude Code")
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(handle_menu_event)
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                windows::reopen(tray.app_handle());
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        tray = tray.icon(icon.clone());
+    }
+    tray.build(app)?;
+
+    let handle = app.clone();
+    let status_items = items.clone();
+    app.listen(STATUS_EVENT, move |_| {
+        let state = handle.state::<Supervisor>().status().state;
+        let items = status_items.clone();
+        let _ = handle.run_on_main_thread(move || items.show_state(state));
+    });
+    items.show_state(app.state::<Supervisor>().status().state);
+
+    let handle = app.clone();
+    app.listen(chat::ACTIVE_EVENT, move |_| {
+        let count = handle.state::<ChatStreams>().active_count();
+        let items = items.clone();
+        let _ = handle.run_on_main_thread(move || items.show_requests(count));
+    });
+    Ok(())
+}
+
+fn handle_menu_event(app: &AppHandle, event: MenuEvent) {
+    match event.id().as_ref() {
+        OPEN_ID => windows::reopen(app),
+        RESTART_ID => {
+            let supervisor = app.state::<Supervisor>().inner().clone();
+            thread::spawn(move || supervisor.restart());
+        }
+        ABORT_ID => {
+            if let Some(base_url) = app.state::<Supervisor>().base_url() {
+                thread::spawn(move || shutdown::abort_active_requests(&base_url));
+            }
+        }
+        QUIT_ID => app.exit(0),
+        _ => {}
+    }
+}
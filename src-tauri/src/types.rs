@@ -0,0 +1,369 @@
+use serde::{Deserialize, Serialize};
+#[cfg(test)]
+use ts_rs::TS;
+
+// Request and response types of the backend's API, mirroring shared/types.ts.
+// `cargo test` writes their TypeScript to bindings/ and fails when it no
+// longer matches shared/types.ts.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(test, derive(TS), ts(export))]
+pub enum StreamResponseType {
+    ClaudeJson,
+    Error,
+    Done,
+    Aborted,
+}
+
+// One line of the NDJSON stream /api/chat responds with
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(test, derive(TS), ts(export))]
+pub struct StreamResponse {
+    #[serde(rename = "type")]
+    #[cfg_attr(test, ts(inline))]
+    pub kind: StreamResponseType,
+    // SDKMessage object for claude_json
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional, type = "unknown"))]
+    pub data: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(test, derive(TS), ts(export))]
+pub enum PermissionMode {
+    Default,
+    Plan,
+    AcceptEdits,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(test, derive(TS), ts(export))]
+pub struct ChatRequest {
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub session_id: Option<String>,
+    pub request_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub allowed_tools: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub working_directory: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional, inline))]
+    pub permission_mode: Option<PermissionMode>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(test, derive(TS), ts(export))]
+pub struct AbortRequest {
+    pub request_id: String,
+}
+
+// Response of GET /api/requests
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(test, derive(TS), ts(export))]
+pub struct ActiveRequestsResponse {
+    pub request_ids: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(test, derive(TS), ts(export))]
+pub struct ProjectInfo {
+    pub path: String,
+    pub encoded_name: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(test, derive(TS), ts(export))]
+pub struct ProjectsResponse {
+    pub projects: Vec<ProjectInfo>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(test, derive(TS), ts(export))]
+pub struct ConversationSummary {
+    pub session_id: String,
+    pub start_time: String,
+    pub last_time: String,
+    pub message_count: u32,
+    pub last_message_preview: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(test, derive(TS), ts(export))]
+pub struct HistoryListResponse {
+    pub conversations: Vec<ConversationSummary>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(test, derive(TS), ts(export))]
+pub struct ConversationMetadata {
+    pub start_time: String,
+    pub end_time: String,
+    pub message_count: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(test, derive(TS), ts(export))]
+pub struct ConversationHistory {
+    pub session_id: String,
+    // TimestampedSDKMessage objects, left untyped like in shared/types.ts
+    #[cfg_attr(test, ts(type = "unknown[]"))]
+    pub messages: Vec<serde_json::Value>,
+    #[cfg_attr(test, ts(inline))]
+    pub metadata: ConversationMetadata,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(test, derive(TS), ts(export))]
+pub struct ApiConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub api_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub base_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub model: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(test, derive(TS), ts(export))]
+pub struct UserConfigResponse {
+    pub use_system_defaults: bool,
+    // Masked, e.g. "sk-...xyz"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub api_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub base_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub model: Option<String>,
+}
+
+// The API key is never sent to the frontend
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(test, derive(TS), ts(export))]
+pub struct SystemConfigResponse {
+    pub has_system_config: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub base_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub model: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(test, derive(TS), ts(export))]
+pub struct ConfigTestRequest {
+    pub api_key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub base_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub model: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(test, derive(TS), ts(export))]
+pub struct ConfigTestResponse {
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(test, derive(TS), ts(export))]
+pub struct SaveConfigRequest {
+    pub use_system_defaults: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub api_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub base_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub model: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHARED_TYPES: &str = include_str!("../../shared/types.ts");
+
+    // Object type bodies from both sides, reduced to `name?:type` members
+    // without whitespace, comments or separators, so only real differences
+    // show up
+    fn members(body: &str) -> Vec<String> {
+        let mut members = Vec::new();
+        let mut depth = 0;
+        let mut member = String::new();
+        for c in strip_comments(body).chars() {
+            match c {
+                '{' | '<' | '(' | '[' => depth += 1,
+                '}' | '>' | ')' | ']' => depth -= 1,
+                ',' | ';' | '\n' if depth == 0 => {
+                    push_member(&mut members, &member);
+                    member.clear();
+                    continue;
+                }
+                _ => {}
+            }
+            member.push(c);
+        }
+        push_member(&mut members, &member);
+        members
+    }
+
+    fn push_member(members: &mut Vec<String>, member: &str) {
+        let Some((name, ty)) = member.split_once(':') else {
+            return;
+        };
+        members.push(format!("{}:{}", name.trim(), normalize_type(ty)));
+    }
+
+    fn normalize_type(ty: &str) -> String {
+        let ty = ty.trim();
+        if let Some(body) = ty.strip_prefix('{').and_then(|ty| ty.strip_suffix('}')) {
+            return format!("{{{}}}", members(body).join(";"));
+        }
+        if let Some(item) = ty
+            .strip_prefix("Array<")
+            .and_then(|ty| ty.strip_suffix('>'))
+        {
+            return format!("{}[]", normalize_type(item));
+        }
+        ty.split_whitespace().collect()
+    }
+
+    fn strip_comments(source: &str) -> String {
+        let mut stripped = String::new();
+        let mut rest = source;
+        while let Some(start) = rest.find("//").into_iter().chain(rest.find("/*")).min() {
+            stripped.push_str(&rest[..start]);
+            let end = if rest[start..].starts_with("//") {
+                rest[start..].find('\n').map(|end| start + end)
+            } else {
+                rest[start..].find("*/").map(|end| start + end + 2)
+            };
+            rest = end.map_or("", |end| &rest[end..]);
+        }
+        stripped.push_str(rest);
+        stripped
+    }
+
+    // The body between the braces following `start`
+    fn braced_body<'a>(source: &'a str, start: &str) -> Option<&'a str> {
+        let open = source.find(start)? + start.len();
+        let open = open + source[open..].find('{')?;
+        let mut depth = 0;
+        for (i, c) in source[open..].char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(&source[open + 1..open + i]);
+                    }
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    fn assert_matches_shared<T: TS>() {
+        let name = T::name();
+        let shared = braced_body(SHARED_TYPES, &format!("export interface {} ", name))
+            .unwrap_or_else(|| panic!("shared/types.ts has no interface {}", name));
+        let decl = T::decl();
+        let generated = braced_body(&decl, "=")
+            .unwrap_or_else(|| panic!("unexpected declaration for {}: {}", name, decl));
+        assert_eq!(
+            members(generated),
+            members(shared),
+            "src/types.rs and shared/types.ts disagree on {}",
+            name
+        );
+    }
+
+    #[test]
+    fn bindings_match_shared_types() {
+        assert_matches_shared::<StreamResponse>();
+        assert_matches_shared::<ChatRequest>();
+        assert_matches_shared::<AbortRequest>();
+        assert_matches_shared::<ActiveRequestsResponse>();
+        assert_matches_shared::<ProjectInfo>();
+        assert_matches_shared::<ProjectsResponse>();
+        assert_matches_shared::<ConversationSummary>();
+        assert_matches_shared::<HistoryListResponse>();
+        assert_matches_shared::<ConversationHistory>();
+        assert_matches_shared::<ApiConfig>();
+        assert_matches_shared::<UserConfigResponse>();
+        assert_matches_shared::<SystemConfigResponse>();
+        assert_matches_shared::<ConfigTestRequest>();
+        assert_matches_shared::<ConfigTestResponse>();
+        assert_matches_shared::<SaveConfigRequest>();
+    }
+
+    #[test]
+    fn every_shared_interface_is_mirrored() {
+        let mirrored = [
+            StreamResponse::name(),
+            ChatRequest::name(),
+            AbortRequest::name(),
+            ActiveRequestsResponse::name(),
+            ProjectInfo::name(),
+            ProjectsResponse::name(),
+            ConversationSummary::name(),
+            HistoryListResponse::name(),
+            ConversationHistory::name(),
+            ApiConfig::name(),
+            UserConfigResponse::name(),
+            SystemConfigResponse::name(),
+            ConfigTestRequest::name(),
+            ConfigTestResponse::name(),
+            SaveConfigRequest::name(),
+        ];
+        for line in SHARED_TYPES.lines() {
+            if let Some(rest) = line.strip_prefix("export interface ") {
+                let name = rest.split_whitespace().next().unwrap_or_default();
+                assert!(
+                    mirrored.iter().any(|mirrored| mirrored == name),
+                    "{} in shared/types.ts has no counterpart in src/types.rs",
+                    name
+                );
+            }
+        }
+    }
+}
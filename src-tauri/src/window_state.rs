@@ -0,0 +1,341 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use tauri::utils::config::WindowConfig;
+use tauri::{AppHandle, Manager, Monitor, WebviewUrl, Window};
+
+use crate::splash::SPLASH_WINDOW;
+
+// Stored next to settings.json in the app config directory
+const WINDOW_STATE_FILE: &str = "windows.json";
+
+// How much of a restored window's top edge must land on a monitor for its
+// title bar to be reachable
+const MIN_VISIBLE_WIDTH: f64 = 100.0;
+const MIN_VISIBLE_HEIGHT: f64 = 40.0;
+
+// Where a window was and what it was showing, in logical pixels
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowState {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub maximized: bool,
+    // Path and query of the page, e.g. "/projects/home/me/app?sessionId=..."
+    pub route: Option<String>,
+}
+
+impl WindowState {
+    // Only project pages are restored; anything else opens the project list
+    pub fn url(&self) -> WebviewUrl {
+        match &self.route {
+            Some(route) if route.starts_with("/projects/") => {
+                WebviewUrl::App(PathBuf::from(&route[1..]))
+            }
+            _ => WebviewUrl::default(),
+        }
+    }
+
+    // Put the window back where it was, unless that position is no longer on
+    // any monitor, in which case it is centered with a size that fits
+    pub fn apply(&self, config: &mut WindowConfig, monitors: &[Monitor]) {
+        let areas: Vec<WorkArea> = monitors.iter().map(WorkArea::of).collect();
+        self.place(config, &areas);
+    }
+
+    fn place(&self, config: &mut WindowConfig, areas: &[WorkArea]) {
+        config.width = self.width.max(config.min_width.unwrap_or(0.0));
+        config.height = self.height.max(config.min_height.unwrap_or(0.0));
+        config.maximized = self.maximized;
+
+        if areas.iter().any(|area| self.title_bar_on(area)) {
+            config.x = Some(self.x);
+            config.y = Some(self.y);
+            config.center = false;
+            return;
+        }
+
+        config.x = None;
+        config.y = None;
+        config.center = true;
+        if let Some(area) = areas.first() {
+            config.width = config.width.min(area.width);
+            config.height = config.height.min(area.height);
+        }
+    }
+
+    fn title_bar_on(&self, area: &WorkArea) -> bool {
+        let visible_width = (self.x + self.width).min(area.x + area.width) - self.x.max(area.x);
+        visible_width >= MIN_VISIBLE_WIDTH.min(self.width)
+            && self.y >= area.y
+            && self.y + MIN_VISIBLE_HEIGHT <= area.y + area.height
+    }
+}
+
+// The part of a monitor not covered by task bars and docks, in logical pixels
+#[derive(Clone, Copy, Debug)]
+struct WorkArea {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+impl WorkArea {
+    fn of(monitor: &Monitor) -> Self {
+        let area = monitor.work_area();
+        let scale = monitor.scale_factor();
+        Self {
+            x: area.position.x as f64 / scale,
+            y: area.position.y as f64 / scale,
+            width: area.size.width as f64 / scale,
+            height: area.size.height as f64 / scale,
+        }
+    }
+}
+
+// Window states from the last session, and those of the windows open now
+#[derive(Clone)]
+pub struct WindowStateStore {
+    path: Option<PathBuf>,
+    saved: Arc<Mutex<Vec<WindowState>>>,
+    windows: Arc<Mutex<BTreeMap<String, WindowState>>>,
+}
+
+impl WindowStateStore {
+    // A missing or unreadable file means every window opens with the defaults
+    pub fn load(app: &AppHandle) -> Self {
+        let path = match app.path().app_config_dir() {
+            Ok(dir) => Some(dir.join(WINDOW_STATE_FILE)),
+            Err(e) => {
+                log::error!("No config directory for the window state file: {}", e);
+                None
+            }
+        };
+
+        let saved = path
+            .as_ref()
+            .and_then(|path| match fs::read_to_string(path) {
+                Ok(contents) => match serde_json::from_str(&contents) {
+                    Ok(saved) => Some(saved),
+                    Err(e) => {
+                        log::warn!("Ignoring invalid {}: {}", path.display(), e);
+                        None
+                    }
+                },
+                Err(_) => None,
+            })
+            .unwrap_or_default();
+
+        Self {
+            path,
+            saved: Arc::new(Mutex::new(saved)),
+            windows: Arc::new(Mutex::new(BTreeMap::new())),
+        }
+    }
+
+    // The windows to reopen at startup, main window first; only handed out once
+    pub fn take_saved(&self) -> Vec<WindowState> {
+        std::mem::take(&mut *self.saved.lock().unwrap())
+    }
+
+    // After the last window closed, reopen it the way it was left
+    pub fn reopen_closed(&self) {
+        let closed = std::mem::take(&mut *self.windows.lock().unwrap());
+        *self.saved.lock().unwrap() = closed.into_values().collect();
+    }
+
+    // Remember where `window` is now and what it shows
+    pub fn track(&self, window: &Window) {
+        if window.label() == SPLASH_WINDOW {
+            return;
+        }
+        let mut windows = self.windows.lock().unwrap();
+        let previous = windows.get(window.label());
+        if let Some(state) = capture(window, previous) {
+            windows.insert(window.label().to_string(), state);
+        }
+    }
+
+    // A closed window is only reopened if it was the last one
+    pub fn closed(&self, window: &Window) {
+        let others_open = window
+            .app_handle()
+            .webview_windows()
+            .keys()
+            .any(|label| label != window.label() && label != SPLASH_WINDOW);
+        if others_open {
+            self.windows.lock().unwrap().remove(window.label());
+        }
+    }
+
+    pub fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let windows: Vec<WindowState> = self.windows.lock().unwrap().values().cloned().collect();
+        // Nothing was opened, e.g. the backend never came up
+        if windows.is_empty() {
+            return;
+        }
+        let result = serde_json::to_string_pretty(&windows)
+            .map_err(|e| e.to_string())
+            .and_then(|contents| {
+                if let Some(dir) = path.parent() {
+                    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+                }
+                let temp = path.with_extension("json.tmp");
+                fs::write(&temp, contents)
+                    .and_then(|_| fs::rename(&temp, path))
+                    .map_err(|e| e.to_string())
+            });
+        if let Err(e) = result {
+            log::error!("Failed to save {}: {}", path.display(), e);
+        }
+    }
+}
+
+// Minimized windows report bogus positions, and maximized ones should come
+// back to their last normal size when unmaximized, so both keep the
+// previously recorded geometry
+fn capture(window: &Window, previous: Option<&WindowState>) -> Option<WindowState> {
+    let route = window
+        .app_handle()
+        .get_webview_window(window.label())
+        .and_then(|webview| webview.url().ok())
+        .map(|url| match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        });
+
+    if window.is_minimized().unwrap_or(false) {
+        return previous.map(|previous| WindowState {
+            route,
+            ..previous.clone()
+        });
+    }
+    let maximized = window.is_maximized().unwrap_or(false);
+    if maximized {
+        if let Some(previous) = previous {
+            return Some(WindowState {
+                maximized,
+                route,
+                ..previous.clone()
+            });
+        }
+    }
+
+    let scale = window.scale_factor().ok()?;
+    let position = window.outer_position().ok()?.to_logical::<f64>(scale);
+    let size = window.inner_size().ok()?.to_logical::<f64>(scale);
+    Some(WindowState {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        maximized,
+        route,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRIMARY: WorkArea = WorkArea {
+        x: 0.0,
+        y: 0.0,
+        width: 1920.0,
+        height: 1040.0,
+    };
+
+    // A second monitor to the left of the primary one
+    const LEFT: WorkArea = WorkArea {
+        x: -1280.0,
+        y: -200.0,
+        width: 1280.0,
+        height: 1024.0,
+    };
+
+    fn state(x: f64, y: f64, width: f64, height: f64) -> WindowState {
+        WindowState {
+            x,
+            y,
+            width,
+            height,
+            maximized: false,
+            route: None,
+        }
+    }
+
+    fn place(state: &WindowState, areas: &[WorkArea]) -> WindowConfig {
+        let mut config = WindowConfig {
+            min_width: Some(800.0),
+            min_height: Some(600.0),
+            ..WindowConfig::default()
+        };
+        state.place(&mut config, areas);
+        config
+    }
+
+    #[test]
+    fn restores_a_window_on_a_connected_monitor() {
+        let config = place(&state(100.0, 50.0, 1200.0, 800.0), &[PRIMARY]);
+        assert_eq!((config.x, config.y), (Some(100.0), Some(50.0)));
+        assert_eq!((config.width, config.height), (1200.0, 800.0));
+        assert!(!config.center);
+    }
+
+    #[test]
+    fn centers_a_window_left_on_a_disconnected_monitor() {
+        let config = place(&state(-3000.0, 100.0, 2400.0, 1200.0), &[PRIMARY]);
+        assert_eq!((config.x, config.y), (None, None));
+        assert!(config.center);
+        // Shrunk to fit the remaining monitor
+        assert_eq!((config.width, config.height), (1920.0, 1040.0));
+
+        let config = place(&state(2500.0, 100.0, 1000.0, 700.0), &[PRIMARY]);
+        assert!(config.center);
+        assert_eq!((config.width, config.height), (1000.0, 700.0));
+    }
+
+    #[test]
+    fn keeps_a_partially_visible_window_whose_title_bar_can_be_grabbed() {
+        // 150 pixels of the top edge stick out on the right
+        let config = place(&state(1770.0, 200.0, 1000.0, 700.0), &[PRIMARY]);
+        assert_eq!(config.x, Some(1770.0));
+
+        // Only 50 pixels do
+        let config = place(&state(1870.0, 200.0, 1000.0, 700.0), &[PRIMARY]);
+        assert!(config.center);
+
+        // The title bar is above the top of the screen
+        let config = place(&state(100.0, -10.0, 1000.0, 700.0), &[PRIMARY]);
+        assert!(config.center);
+
+        // The title bar is below the bottom of the screen
+        let config = place(&state(100.0, 1020.0, 1000.0, 700.0), &[PRIMARY]);
+        assert!(config.center);
+    }
+
+    #[test]
+    fn restores_a_window_on_a_monitor_with_a_negative_origin() {
+        let window = state(-1200.0, -150.0, 1000.0, 700.0);
+        let config = place(&window, &[PRIMARY, LEFT]);
+        assert_eq!((config.x, config.y), (Some(-1200.0), Some(-150.0)));
+
+        let config = place(&window, &[PRIMARY]);
+        assert!(config.center);
+    }
+
+    #[test]
+    fn enforces_the_minimum_size() {
+        let config = place(&state(100.0, 100.0, 300.0, 200.0), &[PRIMARY]);
+        assert_eq!((config.width, config.height), (800.0, 600.0));
+    }
+}
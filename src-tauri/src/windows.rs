@@ -0,0 +1,144 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+
+use crate::launch::PendingOpen;
+use crate::splash::{self, MAIN_WINDOW};
+use crate::window_state::{WindowState, WindowStateStore};
+
+// Labels of windows opened after the main one; capabilities/default.json
+// grants them the same permissions
+const WINDOW_LABEL_PREFIX: &str = "window-";
+
+static NEXT_WINDOW: AtomicU32 = AtomicU32::new(1);
+
+// Build an app window from the "main" entry in tauri.conf.json under another
+// label, optionally where a previous session left it. Every window talks to
+// the one supervised backend.
+pub fn build(
+    app: &AppHandle,
+    label: &str,
+    url: WebviewUrl,
+    state: Option<&WindowState>,
+) -> Result<WebviewWindow, String> {
+    let mut config = app
+        .config()
+        .app
+        .windows
+        .iter()
+        .find(|window| window.label == MAIN_WINDOW)
+        .cloned()
+        .ok_or_else(|| format!("No \"{}\" window in tauri.conf.json", MAIN_WINDOW))?;
+    config.label = label.to_string();
+    config.url = url;
+    if let Some(state) = state {
+        state.apply(&mut config, &app.available_monitors().unwrap_or_default());
+    }
+
+    // Expose the backend's address before any page script runs (see frontend/src/config/api.ts)
+    let script = splash::backend_url_script();
+    WebviewWindowBuilder::from_config(app, &config)
+        .and_then(|builder| builder.initialization_script(&script).build())
+        .map_err(|e| e.to_string())
+}
+
+// Reopen the windows of the last session, or just the main window on a
+// first start
+pub fn open_initial(app: &AppHandle) -> Result<(), String> {
+    let saved = app.state::<WindowStateStore>().take_saved();
+    let mut saved = saved.iter();
+    let main = saved.next();
+    // A project asked for on the command line or by a link wins over the
+    // one the window last showed
+    let url = match app.state::<PendingOpen>().take() {
+        Some(request) => project_url(&request.project_path, request.session_id.as_deref()),
+        None => main.map(WindowState::url).unwrap_or_default(),
+    };
+    build(app, MAIN_WINDOW, url, main)?;
+    for state in saved {
+        if let Err(e) = build(app, &next_label(app), state.url(), Some(state)) {
+            log::error!("Failed to reopen a window: {}", e);
+        }
+    }
+    Ok(())
+}
+
+// Bring an open window to the front, the main one if it is still open;
+// returns false if there is none
+pub fn focus(app: &AppHandle) -> bool {
+    let windows = app.webview_windows();
+    let Some(window) = windows.get(MAIN_WINDOW).or_else(|| windows.values().next()) else {
+        return false;
+    };
+    let _ = window.unminimize();
+    let _ = window.show();
+    let _ = window.set_focus();
+    true
+}
+
+// Show the app again while it runs in the background
+pub fn reopen(app: &AppHandle) {
+    if focus(app) {
+        return;
+    }
+    app.state::<WindowStateStore>().reopen_closed();
+    if let Err(e) = open_initial(app) {
+        log::error!("Failed to reopen the main window: {}", e);
+    }
+}
+
+// Open another window, showing `project` (and in it `session`) if given and
+// the project list otherwise
+pub fn open(
+    app: &AppHandle,
+    project: Option<&str>,
+    session: Option<&str>,
+) -> Result<WebviewWindow, String> {
+    let url = match project {
+        Some(project) => project_url(project, session),
+        None => WebviewUrl::default(),
+    };
+    let window = build(app, &next_label(app), url, None)?;
+    let _ = window.set_focus();
+    Ok(window)
+}
+
+fn next_label(app: &AppHandle) -> String {
+    loop {
+        let label = format!(
+            "{}{}",
+            WINDOW_LABEL_PREFIX,
+            NEXT_WINDOW.fetch_add(1, Ordering::SeqCst)
+        );
+        if app.get_webview_window(&label).is_none() {
+            return label;
+        }
+    }
+}
+
+// ChatPage for a project, as ProjectSelector navigates to it, optionally
+// loading one of its conversations
+fn project_url(project: &str, session: Option<&str>) -> WebviewUrl {
+    let path = project.replace('\\', "/");
+    let path = path.strip_prefix('/').unwrap_or(&path);
+    let mut route = format!("projects/{}", encode(path));
+    if let Some(session) = session {
+        route.push_str("?sessionId=");
+        route.push_str(&encode(session).replace('/', "%2F"));
+    }
+    WebviewUrl::App(PathBuf::from(route))
+}
+
+fn encode(value: &str) -> String {
+    let mut encoded = String::new();
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' | b':' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}